@@ -0,0 +1,38 @@
+//! Exports every enemy in scene.bin as bestiary.json and bestiary.csv.
+//!
+//! Usage: cargo run --example export_bestiary -- <scene.bin> <output dir> [kernel.bin]
+//!
+//! Item names are taken from kernel.bin when it is given.
+
+use ff7_lib::ff7::bestiary::{bestiary_to_csv, bestiary_to_json, build_bestiary, kernel_item_names};
+use ff7_lib::ff7::data::battle::read_scene_bin_from_path;
+use ff7_lib::ff7::data::kernel_bin::read_kernel_bin_from_path;
+use std::path::Path;
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (scene_bin, output_dir) = match args {
+        [scene_bin, output_dir, ..] => (Path::new(scene_bin), Path::new(output_dir)),
+        _ => return Err("Usage: export_bestiary <scene.bin> <output dir> [kernel.bin]".to_string()),
+    };
+
+    let scenes = read_scene_bin_from_path(scene_bin)?;
+    let item_names = match args.get(2) {
+        Some(kernel_bin) => kernel_item_names(&read_kernel_bin_from_path(Path::new(kernel_bin))?.texts),
+        None => Vec::new(),
+    };
+    let bestiary = build_bestiary(&scenes, &item_names);
+
+    std::fs::create_dir_all(output_dir).map_err(|e| e.to_string())?;
+    std::fs::write(output_dir.join("bestiary.json"), bestiary_to_json(&bestiary)?).map_err(|e| e.to_string())?;
+    std::fs::write(output_dir.join("bestiary.csv"), bestiary_to_csv(&bestiary)).map_err(|e| e.to_string())?;
+    println!("Exported {} enemies to {}", bestiary.len(), output_dir.display());
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
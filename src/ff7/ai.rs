@@ -0,0 +1,489 @@
+use crate::ff7::ff7text::decode_text;
+use crate::ff7::types::ai::{AIInstruction, AIOperand, AIVariableBank};
+use crate::ff7::types::battle::{SceneAIData, SceneAIScript, SceneAttack};
+
+fn mnemonic(opcode: u8) -> &'static str {
+    match opcode {
+        0x00..=0x03 => "PSHV",
+        0x10..=0x13 => "PSHA",
+        0x30 => "ADD",
+        0x31 => "SUB",
+        0x32 => "MUL",
+        0x33 => "DIV",
+        0x34 => "MOD",
+        0x35 => "BAND",
+        0x36 => "BOR",
+        0x37 => "BNOT",
+        0x40 => "EQU",
+        0x41 => "NEQ",
+        0x42 => "GEQ",
+        0x43 => "LEQ",
+        0x44 => "GTN",
+        0x45 => "LTN",
+        0x50 => "AND",
+        0x51 => "OR",
+        0x52 => "NOT",
+        0x60 => "PSH1",
+        0x61 => "PSH2",
+        0x62 => "PSH3",
+        0x70 => "JMPZ",
+        0x71 => "JMPNE",
+        0x72 => "JMP",
+        0x73 => "END",
+        0x74 => "POP",
+        0x75 => "LINK",
+        0x80 => "MASK",
+        0x81 => "RNDW",
+        0x82 => "RNDB",
+        0x83 => "CNTB",
+        0x84 => "HMSK",
+        0x85 => "LMSK",
+        0x86 => "MPCS",
+        0x87 => "TOBIT",
+        0x90 => "STORE",
+        0x91 => "DROP",
+        0x92 => "ATTK",
+        0x93 => "SHOW",
+        0x94 => "SCENE",
+        0x95 => "GLOB",
+        0x96 => "ELEM",
+        0x97 => "WRITE",
+        0x98 => "ANIM",
+        0x99 => "ENDB",
+        0xA0 => "DEBUG",
+        0xA1 => "ACTOR",
+        0xA2 => "MPSET",
+        _ => "UNKNOWN",
+    }
+}
+
+fn read_u16(bytecode: &[u8], pos: usize) -> Result<u16, String> {
+    bytecode
+        .get(pos..pos + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| format!("Truncated operand at 0x{:04X}", pos))
+}
+
+fn read_text(bytecode: &[u8], pos: usize, terminator: u8) -> Result<Vec<u8>, String> {
+    let length = bytecode
+        .get(pos..)
+        .unwrap_or_default()
+        .iter()
+        .position(|&b| b == terminator)
+        .ok_or_else(|| format!("Unterminated string at 0x{:04X}", pos))?;
+    Ok(bytecode[pos..pos + length].to_vec())
+}
+
+/// Splits a script into instructions. Offsets are relative to the start of the script,
+/// the same base the jump opcodes use for their targets.
+pub fn disassemble_script(script: &SceneAIScript) -> Result<Vec<AIInstruction>, String> {
+    let bytecode = &script.bytecode;
+    let mut instructions = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytecode.len() {
+        let opcode = bytecode[pos];
+        let offset = pos as u16;
+        pos += 1;
+
+        let operand = match opcode {
+            0x00..=0x03 | 0x10..=0x13 => {
+                let address = read_u16(bytecode, pos)?;
+                pos += 2;
+                AIOperand::Address(address)
+            }
+            0x60 => {
+                let value = *bytecode
+                    .get(pos)
+                    .ok_or_else(|| format!("Truncated operand at 0x{:04X}", pos))?;
+                pos += 1;
+                AIOperand::Value(value as u32)
+            }
+            0x61 => {
+                let value = read_u16(bytecode, pos)?;
+                pos += 2;
+                AIOperand::Value(value as u32)
+            }
+            0x62 => {
+                let bytes = bytecode
+                    .get(pos..pos + 3)
+                    .ok_or_else(|| format!("Truncated operand at 0x{:04X}", pos))?;
+                pos += 3;
+                AIOperand::Value(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]))
+            }
+            0x70..=0x72 => {
+                let target = read_u16(bytecode, pos)?;
+                pos += 2;
+                AIOperand::Jump(target)
+            }
+            // Battle text is FF7-encoded and 0xFF-terminated
+            0x93 => {
+                let text = read_text(bytecode, pos, 0xFF)?;
+                pos += text.len() + 1;
+                AIOperand::Text(text)
+            }
+            // Debug text is a printf-style ASCII format string, NUL-terminated
+            0xA0 => {
+                let arg_count = *bytecode
+                    .get(pos)
+                    .ok_or_else(|| format!("Truncated operand at 0x{:04X}", pos))?;
+                let text = read_text(bytecode, pos + 1, 0x00)?;
+                pos += text.len() + 2;
+                AIOperand::DebugText { arg_count, text }
+            }
+            _ => AIOperand::None,
+        };
+
+        instructions.push(AIInstruction {
+            offset,
+            opcode,
+            mnemonic: mnemonic(opcode),
+            operand,
+        });
+    }
+
+    Ok(instructions)
+}
+
+/// Well-known battle variables, everything else is printed with its bank and address
+fn variable_name(address: u16) -> String {
+    let known = match address {
+        0x2070 => Some("Targets"),
+        0x4140 => Some("CurrentMP"),
+        0x4150 => Some("MaxMP"),
+        0x4160 => Some("CurrentHP"),
+        0x4180 => Some("MaxHP"),
+        _ => None,
+    };
+    match (known, AIVariableBank::from_address(address)) {
+        (Some(name), _) => name.to_string(),
+        (None, AIVariableBank::Local) => format!("Local[{:04X}]", address),
+        (None, AIVariableBank::Global) => format!("Global[{:04X}]", address),
+        (None, AIVariableBank::Actor) => format!("Actor[{:04X}]", address),
+    }
+}
+
+fn binary_operator(opcode: u8) -> Option<&'static str> {
+    match opcode {
+        0x30 => Some("+"),
+        0x31 => Some("-"),
+        0x32 => Some("*"),
+        0x33 => Some("/"),
+        0x34 => Some("%"),
+        0x35 => Some("&"),
+        0x36 => Some("|"),
+        0x40 => Some("=="),
+        0x41 => Some("!="),
+        0x42 => Some(">="),
+        0x43 => Some("<="),
+        0x44 => Some(">"),
+        0x45 => Some("<"),
+        0x50 => Some("&&"),
+        0x51 => Some("||"),
+        _ => None,
+    }
+}
+
+/// Values consumed by the opcodes that have no case of their own in `Decompiler::block` and
+/// don't leave a result behind. Of these, only 0x94 and 0x95 occur in the original scene.bin.
+fn stack_arguments(opcode: u8) -> usize {
+    match opcode {
+        0x94 | 0x95 | 0x96 | 0xA1 => 2,
+        0x97 | 0x98 | 0x99 | 0xA2 => 1,
+        _ => 0,
+    }
+}
+
+/// Pops one expression, falling back to a placeholder when the script under-runs the stack
+fn pop(stack: &mut Vec<String>) -> String {
+    stack.pop().unwrap_or_else(|| String::from("<empty>"))
+}
+
+/// Wraps an expression in parentheses unless it is already fully enclosed in a pair
+fn parenthesize(expression: String) -> String {
+    let mut depth = 0;
+    for (i, c) in expression.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 && i + 1 < expression.len() {
+            return format!("({})", expression);
+        }
+    }
+    if expression.starts_with('(') {
+        expression
+    } else {
+        format!("({})", expression)
+    }
+}
+
+fn format_value(value: u32) -> String {
+    if value < 10 {
+        value.to_string()
+    } else {
+        format!("0x{:X}", value)
+    }
+}
+
+struct Decompiler<'a> {
+    instructions: Vec<AIInstruction>,
+    end_offset: u16,
+    attacks: &'a [SceneAttack],
+    lines: Vec<String>,
+}
+
+impl Decompiler<'_> {
+    /// Instruction index for a jump target; the end of the script maps to one past the last instruction
+    fn index_of(&self, offset: u16) -> Option<usize> {
+        if offset == self.end_offset {
+            return Some(self.instructions.len());
+        }
+        self.instructions.iter().position(|i| i.offset == offset)
+    }
+
+    fn is_jump_to(&self, index: usize, target_index: usize) -> bool {
+        match self.instructions[index] {
+            AIInstruction {
+                opcode: 0x72,
+                operand: AIOperand::Jump(target),
+                ..
+            } => self.index_of(target) == Some(target_index),
+            _ => false,
+        }
+    }
+
+    /// The script compiler emits a fallthrough jump after every `break` of a switch case,
+    /// which can never run because it directly follows another jump or the end of the script
+    fn is_unreachable_jump(&self, index: usize) -> bool {
+        index > 0
+            && self.instructions[index].opcode == 0x72
+            && matches!(self.instructions[index - 1].opcode, 0x72 | 0x73)
+    }
+
+    fn emit(&mut self, depth: usize, line: String) {
+        self.lines.push(format!("{}{}", "    ".repeat(depth), line));
+    }
+
+    fn attack_call(&self, command: String, attack: String) -> String {
+        let name = u16::from_str_radix(attack.trim_start_matches("0x"), 16)
+            .ok()
+            .and_then(|id| self.attacks.iter().find(|a| a.id == id))
+            .map(|a| format!(" // {}", a.name))
+            .unwrap_or_default();
+        format!("attack({}, {});{}", command, attack, name)
+    }
+
+    /// Decompiles instructions from `start` up to (not including) the instruction at `end`,
+    /// rebuilding if/else blocks from forward conditional jumps.
+    fn block(&mut self, start: usize, end: usize, depth: usize, stack: &mut Vec<String>) {
+        let mut index = start;
+        while index < end {
+            let instruction = self.instructions[index].clone();
+            index += 1;
+
+            match (instruction.opcode, &instruction.operand) {
+                (0x00..=0x03, AIOperand::Address(address)) => stack.push(variable_name(*address)),
+                (0x10..=0x13, AIOperand::Address(address)) => stack.push(format!("&{}", variable_name(*address))),
+                (0x60..=0x62, AIOperand::Value(value)) => stack.push(format_value(*value)),
+                (opcode, _) if binary_operator(opcode).is_some() => {
+                    let right = pop(stack);
+                    let left = pop(stack);
+                    stack.push(format!("({} {} {})", left, binary_operator(opcode).unwrap(), right));
+                }
+                (0x37, _) => {
+                    let value = pop(stack);
+                    stack.push(format!("~{}", value));
+                }
+                (0x52, _) => {
+                    let value = pop(stack);
+                    stack.push(format!("!{}", value));
+                }
+                (0x80, _) => {
+                    let mask = pop(stack);
+                    let value = pop(stack);
+                    stack.push(format!("{}.mask({})", value, mask));
+                }
+                (0x81, _) => stack.push(String::from("random()")),
+                (0x82..=0x87, _) => {
+                    let value = pop(stack);
+                    stack.push(format!("{}({})", instruction.mnemonic.to_lowercase(), value));
+                }
+                (0x70, AIOperand::Jump(target)) | (0x71, AIOperand::Jump(target)) => {
+                    let condition = if instruction.opcode == 0x70 {
+                        parenthesize(pop(stack))
+                    } else {
+                        // Switch-style compare: the value being tested stays on the stack
+                        let case = pop(stack);
+                        let value = stack.last().cloned().unwrap_or_else(|| String::from("<empty>"));
+                        format!("({} == {})", value, case)
+                    };
+                    let target_index = match self.index_of(*target) {
+                        Some(target_index) if target_index >= index && target_index <= end => target_index,
+                        _ => {
+                            self.emit(depth, format!("if (!{}) goto label_{:04X};", condition, target));
+                            continue;
+                        }
+                    };
+
+                    self.emit(depth, format!("if {} {{", condition));
+                    // A jump at the end of the "then" branch that skips forward marks an else branch
+                    let mut last = target_index;
+                    while last > index && (self.is_jump_to(last - 1, last) || self.is_unreachable_jump(last - 1)) {
+                        last -= 1;
+                    }
+                    let else_end = match self.instructions.get(last.wrapping_sub(1)) {
+                        Some(jump) if last > index && jump.opcode == 0x72 => match jump.operand {
+                            AIOperand::Jump(else_target) => self
+                                .index_of(else_target)
+                                .filter(|&else_index| else_index > target_index && else_index <= end),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    match else_end {
+                        Some(else_index) => {
+                            self.block(index, last - 1, depth + 1, stack);
+                            self.emit(depth, String::from("} else {"));
+                            self.block(target_index, else_index, depth + 1, stack);
+                            index = else_index;
+                        }
+                        None => {
+                            self.block(index, target_index, depth + 1, stack);
+                            index = target_index;
+                        }
+                    }
+                    self.emit(depth, String::from("}"));
+                }
+                // Jumps to the next instruction or to the end of the current block fall through anyway
+                (0x72, _)
+                    if self.is_jump_to(index - 1, index)
+                        || self.is_jump_to(index - 1, end)
+                        || self.is_unreachable_jump(index - 1) => {}
+                (0x72, AIOperand::Jump(target)) => self.emit(depth, format!("goto label_{:04X};", target)),
+                (0x73, _) => self.emit(depth, String::from("return;")),
+                (0x74, _) | (0x91, _) => {
+                    pop(stack);
+                }
+                (0x75, _) => self.emit(depth, String::from("link();")),
+                (0x90, _) => {
+                    let value = pop(stack);
+                    let address = pop(stack);
+                    self.emit(depth, format!("{} = {};", address.trim_start_matches('&'), value));
+                }
+                (0x92, _) => {
+                    let attack = pop(stack);
+                    let command = pop(stack);
+                    let line = self.attack_call(command, attack);
+                    self.emit(depth, line);
+                }
+                (0x93, AIOperand::Text(text)) => {
+                    self.emit(depth, format!("show(\"{}\");", decode_text(text).unwrap_or_default()))
+                }
+                (0xA0, AIOperand::DebugText { arg_count, text }) => {
+                    let args: Vec<String> = (0..*arg_count).map(|_| pop(stack)).collect();
+                    let text = String::from_utf8_lossy(text);
+                    if args.is_empty() {
+                        self.emit(depth, format!("debug(\"{}\");", text));
+                    } else {
+                        self.emit(depth, format!("debug(\"{}\", {});", text, args.join(", ")));
+                    }
+                }
+                (opcode, _) => {
+                    let mut args: Vec<String> = (0..stack_arguments(opcode)).map(|_| pop(stack)).collect();
+                    args.reverse();
+                    let call = format!("{}({});", instruction.mnemonic.to_lowercase(), args.join(", "));
+                    self.emit(depth, format!("{} // 0x{:02X}", call, opcode));
+                }
+            }
+        }
+    }
+}
+
+/// Reconstructs readable pseudo-code for a single script. Attack ids found in
+/// `attacks` are annotated with their names.
+pub fn decompile_script(script: &SceneAIScript, attacks: &[SceneAttack]) -> Result<String, String> {
+    let instructions = disassemble_script(script)?;
+    let count = instructions.len();
+
+    let targets: Vec<u16> = instructions
+        .iter()
+        .filter_map(|i| match i.operand {
+            AIOperand::Jump(target) => Some(target),
+            _ => None,
+        })
+        .collect();
+
+    let mut decompiler = Decompiler {
+        instructions,
+        end_offset: script.bytecode.len() as u16,
+        attacks,
+        lines: Vec::new(),
+    };
+    let mut stack = Vec::new();
+    decompiler.block(0, count, 0, &mut stack);
+
+    let mut output = String::new();
+    for line in &decompiler.lines {
+        output.push_str(line);
+        output.push('\n');
+    }
+    if !output.contains("goto label_") {
+        return Ok(output);
+    }
+
+    // Jumps that don't form if/else blocks: fall back to a flat listing with labels
+    let mut labelled = String::new();
+    let label = |offset: u16| format!("label_{:04X}:\n", offset);
+    decompiler.lines.clear();
+    stack.clear();
+    for index in 0..count {
+        let offset = decompiler.instructions[index].offset;
+        if targets.contains(&offset) {
+            labelled.push_str(&label(offset));
+        }
+        decompiler.block(index, index + 1, 1, &mut stack);
+        for line in decompiler.lines.drain(..) {
+            labelled.push_str(&line);
+            labelled.push('\n');
+        }
+    }
+    if targets.contains(&decompiler.end_offset) {
+        labelled.push_str(&label(decompiler.end_offset));
+    }
+    Ok(labelled)
+}
+
+/// Decompiles every script of an AI block, one section per event
+pub fn decompile_ai(ai: &SceneAIData, attacks: &[SceneAttack]) -> Result<String, String> {
+    let mut output = String::new();
+    for script in &ai.scripts {
+        output.push_str(&format!("// {}\n", script.event.name()));
+        output.push_str(&decompile_script(script, attacks)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Plain text listing of a script, one instruction per line
+pub fn format_disassembly(instructions: &[AIInstruction]) -> String {
+    let mut output = String::new();
+    for instruction in instructions {
+        let operand = match &instruction.operand {
+            AIOperand::None => String::new(),
+            AIOperand::Address(address) => format!(" {:04X}", address),
+            AIOperand::Value(value) => format!(" {:X}", value),
+            AIOperand::Jump(target) => format!(" {:04X}", target),
+            AIOperand::Text(text) => format!(" \"{}\"", decode_text(text).unwrap_or_default()),
+            AIOperand::DebugText { arg_count, text } => {
+                format!(" {} \"{}\"", arg_count, String::from_utf8_lossy(text))
+            }
+        };
+        output.push_str(&format!(
+            "{:04X}: {:02X} {}{}\n",
+            instruction.offset, instruction.opcode, instruction.mnemonic, operand
+        ));
+    }
+    output
+}
@@ -0,0 +1,176 @@
+use crate::ff7::types::battle::{
+    BestiaryElementRate, BestiaryEntry, BestiaryFormation, BestiaryItem, Scene, SceneDatabase, SceneEnemy,
+};
+use crate::ff7::types::kernel::{ElementalEffect, ElementalType, KernelTexts};
+use std::collections::BTreeMap;
+
+const CSV_HEADER: &str = "id,name,level,hp,mp,exp,ap,gil,strength,defense,magic,magic_defense,speed,luck,evade,element_rates,steal,drop,morph,attacks,battle_ids";
+
+/// Item names indexed by scene.bin item ids: items, then weapons, armor and accessories
+pub fn kernel_item_names(texts: &KernelTexts) -> Vec<String> {
+    let mut names = Vec::new();
+    for (section, count) in [
+        (&texts.item_names, 128),
+        (&texts.weapon_names, 128),
+        (&texts.armor_names, 32),
+        (&texts.accessory_names, 32),
+    ] {
+        names.extend((0..count).map(|i| section.get(i).cloned().unwrap_or_default()));
+    }
+    names
+}
+
+fn item_name(item_names: &[String], item_id: u16) -> String {
+    item_names
+        .get(item_id as usize)
+        .filter(|name| !name.is_empty())
+        .cloned()
+        .unwrap_or_else(|| format!("Item {}", item_id))
+}
+
+fn element_rates(enemy: &SceneEnemy) -> Vec<BestiaryElementRate> {
+    enemy
+        .element_rates
+        .iter()
+        .filter(|rate| rate.element_type != 0xFF)
+        .map(|rate| BestiaryElementRate {
+            element: ElementalType::from_index(rate.element_type)
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("Unknown 0x{:02X}", rate.element_type)),
+            effect: ElementalEffect::from_rate(rate.rate)
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("Unknown 0x{:02X}", rate.rate)),
+        })
+        .collect()
+}
+
+fn bestiary_entry(scene: &Scene, enemy: &SceneEnemy, item_names: &[String]) -> BestiaryEntry {
+    let items = |steal: bool| {
+        enemy
+            .items
+            .iter()
+            .filter(|item| item.item_id != 0xFFFF && item.is_steal() == steal)
+            .map(|item| BestiaryItem { name: item_name(item_names, item.item_id), rate: item.rate() })
+            .collect()
+    };
+    let attacks = enemy
+        .enemy_attack_ids
+        .iter()
+        .filter(|id| **id != 0xFFFF)
+        .map(|id| {
+            scene
+                .attacks
+                .iter()
+                .find(|attack| attack.id == *id)
+                .map(|attack| attack.name.clone())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("Attack 0x{:04X}", id))
+        })
+        .collect();
+
+    BestiaryEntry {
+        id: enemy.id,
+        name: enemy.name.clone(),
+        level: enemy.level,
+        hp: enemy.hp,
+        mp: enemy.mp,
+        exp: enemy.exp,
+        ap: enemy.ap,
+        gil: enemy.gil,
+        strength: enemy.strength,
+        defense: enemy.defense,
+        magic: enemy.magic,
+        magic_defense: enemy.magic_defense,
+        speed: enemy.speed,
+        luck: enemy.luck,
+        evade: enemy.evade,
+        element_rates: element_rates(enemy),
+        steal: items(true),
+        drop: items(false),
+        morph: (enemy.morph_item_id != 0xFFFF).then(|| item_name(item_names, enemy.morph_item_id)),
+        attacks,
+        formations: Vec::new(),
+    }
+}
+
+/// One entry per enemy id, sorted by id. Stats come from the first scene that defines the
+/// enemy; formations are collected from every scene.
+pub fn build_bestiary(scenes: &[Scene], item_names: &[String]) -> Vec<BestiaryEntry> {
+    let mut entries: BTreeMap<u16, BestiaryEntry> = BTreeMap::new();
+    for scene in scenes {
+        for enemy in scene.enemies.iter().filter(|enemy| enemy.id != 0xFFFF) {
+            entries.entry(enemy.id).or_insert_with(|| bestiary_entry(scene, enemy, item_names));
+        }
+        for (formation_index, formation) in scene.formations.iter().enumerate() {
+            let formation_ref = BestiaryFormation {
+                battle_id: SceneDatabase::battle_id(scene.id, formation_index as u8),
+                scene_id: scene.id,
+                formation_index: formation_index as u8,
+            };
+            for entry in formation.entries() {
+                if let Some(bestiary_entry) = entries.get_mut(&entry.enemy_id) {
+                    if !bestiary_entry.formations.contains(&formation_ref) {
+                        bestiary_entry.formations.push(formation_ref);
+                    }
+                }
+            }
+        }
+    }
+    entries.into_values().collect()
+}
+
+pub fn bestiary_to_json(entries: &[BestiaryEntry]) -> Result<String, String> {
+    serde_json::to_string_pretty(entries).map_err(|e| e.to_string())
+}
+
+fn csv_field(value: String) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// One row per enemy; list columns are joined with "; "
+pub fn bestiary_to_csv(entries: &[BestiaryEntry]) -> String {
+    let items = |items: &[BestiaryItem]| {
+        items.iter().map(|item| format!("{} ({}/64)", item.name, item.rate)).collect::<Vec<_>>().join("; ")
+    };
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for entry in entries {
+        let fields = [
+            entry.id.to_string(),
+            entry.name.clone(),
+            entry.level.to_string(),
+            entry.hp.to_string(),
+            entry.mp.to_string(),
+            entry.exp.to_string(),
+            entry.ap.to_string(),
+            entry.gil.to_string(),
+            entry.strength.to_string(),
+            entry.defense.to_string(),
+            entry.magic.to_string(),
+            entry.magic_defense.to_string(),
+            entry.speed.to_string(),
+            entry.luck.to_string(),
+            entry.evade.to_string(),
+            entry
+                .element_rates
+                .iter()
+                .map(|rate| format!("{}: {}", rate.element, rate.effect))
+                .collect::<Vec<_>>()
+                .join("; "),
+            items(&entry.steal),
+            items(&entry.drop),
+            entry.morph.clone().unwrap_or_default(),
+            entry.attacks.join("; "),
+            entry.formations.iter().map(|formation| formation.battle_id.to_string()).collect::<Vec<_>>().join("; "),
+        ];
+        csv.push_str(&fields.into_iter().map(csv_field).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
@@ -0,0 +1,191 @@
+use crate::ff7::types::battle::{DamageActor, DamageAttack, DamageContext, DamageEstimate};
+use crate::ff7::types::kernel::{ElementFlags, ElementalEffect, ElementalType, StatusFlags};
+
+const MAX_DAMAGE: i32 = 9999;
+
+/// Random variance roll: damage * (3841..=4096) / 4096
+const VARIANCE_ROLLS: std::ops::RangeInclusive<i32> = 3841..=4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DamageKind {
+    Physical,
+    Magical,
+}
+
+/// Ordered by priority, the first matching outcome wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ElementOutcome {
+    Death,
+    FullCure,
+    Absorb,
+    Nullify,
+    Half,
+    Double,
+    Normal,
+}
+
+fn damage_kind(damage_func: u8) -> Option<DamageKind> {
+    match damage_func >> 4 {
+        1 => Some(DamageKind::Physical),
+        2 => Some(DamageKind::Magical),
+        _ => None,
+    }
+}
+
+/// Picks the strongest matching element rate: Death, Full Cure, Absorb, Nullify, Half, then Double
+fn element_outcome(attack_element: ElementFlags, target: &DamageActor) -> ElementOutcome {
+    target
+        .element_rates
+        .iter()
+        .filter(|rate| attack_element.intersects(ElementalType::from_index(rate.element_type).into()))
+        .map(|rate| match ElementalEffect::from_rate(rate.rate) {
+            ElementalEffect::Death => ElementOutcome::Death,
+            ElementalEffect::FullCure => ElementOutcome::FullCure,
+            ElementalEffect::Absorb => ElementOutcome::Absorb,
+            ElementalEffect::Nullify => ElementOutcome::Nullify,
+            ElementalEffect::HalfDamage => ElementOutcome::Half,
+            ElementalEffect::DoubleDamage => ElementOutcome::Double,
+            ElementalEffect::Nothing => ElementOutcome::Normal,
+        })
+        .min()
+        .unwrap_or(ElementOutcome::Normal)
+}
+
+/// Base damage of the standard formula before any modifiers
+pub fn base_damage(attacker: &DamageActor, target: &DamageActor, attack: &DamageAttack) -> Result<i32, String> {
+    let level = attacker.level as i32;
+    let power = attack.power as i32;
+    let (base, defense) = match damage_kind(attack.damage_func) {
+        Some(DamageKind::Physical) => {
+            let attack_stat = attacker.attack as i32;
+            (attack_stat + ((attack_stat + level) / 32) * ((attack_stat * level) / 32), target.defense as i32)
+        }
+        Some(DamageKind::Magical) => (6 * (attacker.magic as i32 + level), target.magic_defense as i32),
+        None => return Err(format!("Damage function 0x{:02X} is neither physical nor magical", attack.damage_func)),
+    };
+    let defense = defense.min(511);
+    Ok((power as i64 * (512 - defense) as i64 * base as i64 / (16 * 512)) as i32)
+}
+
+/// Applies the modifiers that come between the base damage and the random variance
+fn apply_modifiers(
+    damage: i32,
+    kind: DamageKind,
+    attacker: &DamageActor,
+    target: &DamageActor,
+    context: &DamageContext,
+) -> i32 {
+    let mut damage = damage;
+    if context.split {
+        damage = damage * 2 / 3;
+    }
+    if kind == DamageKind::Physical {
+        if context.critical {
+            damage *= 2;
+        }
+        if attacker.status.contains(StatusFlags::BERSERK) {
+            damage = damage * 3 / 2;
+        }
+        if !context.long_range && attacker.back_row {
+            damage /= 2;
+        }
+        if !context.long_range && target.back_row {
+            damage /= 2;
+        }
+        if context.defending {
+            damage /= 2;
+        }
+        if context.back_attack {
+            damage = damage * target.back_damage_multiplier as i32 / 8;
+        }
+        if attacker.status.contains(StatusFlags::FROG) {
+            damage /= 4;
+        }
+        if target.status.contains(StatusFlags::BARRIER) {
+            damage /= 2;
+        }
+        if attacker.status.contains(StatusFlags::SMALL) {
+            damage = 0;
+        }
+    } else if target.status.contains(StatusFlags::MBARRIER) {
+        damage /= 2;
+    }
+    if target.status.contains(StatusFlags::SADNESS) {
+        damage = damage * 7 / 10;
+    }
+    damage
+}
+
+fn apply_element(damage: i32, outcome: ElementOutcome) -> i32 {
+    let damage = match outcome {
+        ElementOutcome::Nullify => return 0,
+        ElementOutcome::Half => damage / 2,
+        ElementOutcome::Double => damage * 2,
+        _ => damage,
+    };
+    // Anything that connects does at least 1 point of damage, absorbed hits heal at least 1
+    let damage = damage.clamp(1, MAX_DAMAGE);
+    if outcome == ElementOutcome::Absorb {
+        -damage
+    } else {
+        damage
+    }
+}
+
+fn estimate(values: &[i32], instant_death: bool) -> DamageEstimate {
+    DamageEstimate {
+        min: *values.iter().min().unwrap(),
+        max: *values.iter().max().unwrap(),
+        expected: values.iter().map(|value| *value as f32).sum::<f32>() / values.len() as f32,
+        instant_death,
+    }
+}
+
+/// Every damage value the random variance can produce, one per roll, and whether the
+/// target's Death element rate applies. Negative values are healing.
+pub fn damage_rolls(
+    attacker: &DamageActor,
+    target: &DamageActor,
+    attack: &DamageAttack,
+    context: &DamageContext,
+) -> Result<(Vec<i32>, bool), String> {
+    let outcome = element_outcome(attack.attack_element, target);
+    match outcome {
+        ElementOutcome::Death => return Ok((vec![target.hp as i32], true)),
+        ElementOutcome::FullCure => return Ok((vec![-((target.max_hp - target.hp.min(target.max_hp)) as i32)], false)),
+        _ => {}
+    }
+
+    let power = attack.power as i32;
+    let rolls = match attack.damage_func & 0x0F {
+        0x0 => vec![0],
+        0x1 => {
+            let kind = damage_kind(attack.damage_func)
+                .ok_or_else(|| format!("Damage function 0x{:02X} is neither physical nor magical", attack.damage_func))?;
+            let damage = apply_modifiers(base_damage(attacker, target, attack)?, kind, attacker, target, context);
+            VARIANCE_ROLLS.map(|roll| apply_element(damage * roll / 4096, outcome)).collect()
+        }
+        0x2 => vec![apply_element(target.hp as i32 * power / 32, outcome)],
+        0x3 => vec![apply_element(target.max_hp as i32 * power / 32, outcome)],
+        0x4 => vec![apply_element(power * 20, outcome)],
+        0x5 => {
+            let healing = power * 22 + (attacker.level as i32 + attacker.magic as i32) * 6;
+            VARIANCE_ROLLS.map(|roll| -(healing * roll / 4096).min(MAX_DAMAGE)).collect()
+        }
+        formula => return Err(format!("Damage formula 0x{:X} is not supported", formula)),
+    };
+    Ok((rolls, false))
+}
+
+/// Damage range for one attacker/target pair. Supported formulas (lower nibble of
+/// `damage_func`): 0 no damage, 1 standard, 2 current HP %, 3 max HP %, 4 fixed and
+/// 5 recovery. Negative values are healing.
+pub fn calculate_damage(
+    attacker: &DamageActor,
+    target: &DamageActor,
+    attack: &DamageAttack,
+    context: &DamageContext,
+) -> Result<DamageEstimate, String> {
+    let (rolls, instant_death) = damage_rolls(attacker, target, attack, context)?;
+    Ok(estimate(&rolls, instant_death))
+}
@@ -1,18 +1,139 @@
 use crate::ff7::addresses::FF7Addresses;
-use crate::ff7::data::kernel::read_name;
+use crate::ff7::data::kernel::{read_attack_names, read_command_names, read_item_names, read_name};
+use crate::ff7::data::kernel_bin::{encode_attack_data, parse_attack_data};
 use crate::ff7::types::battle::*;
 use crate::ff7::types::items::{Item, ItemType};
-use crate::ff7::types::kernel::Elemental;
+use crate::ff7::types::kernel::{AttackData, Elemental, ElementDefense, ElementFlags, KernelBin, StatusFlags};
 use crate::utils::memory::*;
 use crate::utils::process;
 use byteorder::{ReadBytesExt, LittleEndian};
 use std::fs;
 use std::io::{Read, Cursor, Seek, SeekFrom};
 use std::path::Path;
-use flate2::read::GzDecoder;
-use crate::ff7::ff7text::decode_text; // Needed for names
+use flate2::bufread::GzDecoder;
+use flate2::{Compression, GzBuilder};
+use std::io::Write;
+use crate::ff7::ff7text::{decode_text, encode_text}; // Needed for names
 
-pub fn read_battle_allies(addresses: &FF7Addresses) -> Result<Vec<BattleCharObj>, String> {
+const BATTLE_ACTOR_LENGTH: u32 = 0x68;
+const BATTLE_ACTOR_COUNT: u32 = 10;
+const BATTLE_TIMER_LENGTH: u32 = 0x44;
+
+/// Slot 3 sits between the party and the enemies and is never used by an actor
+const UNUSED_BATTLE_ACTOR: u32 = 3;
+
+fn battle_actor_address(addresses: &FF7Addresses, index: u32) -> Result<u32, String> {
+    if index >= BATTLE_ACTOR_COUNT || index == UNUSED_BATTLE_ACTOR {
+        return Err(format!("Invalid battle actor index {}", index));
+    }
+    Ok(addresses.battle_char_base + index * BATTLE_ACTOR_LENGTH)
+}
+
+fn battle_atb_address(addresses: &FF7Addresses, index: u32) -> u32 {
+    addresses.battle_atb_base + index * BATTLE_TIMER_LENGTH + 0x2
+}
+
+/// Reads the full 0x68-byte actor record. Offsets match the 0x4000 actor variables
+/// used by the AI scripts (variable address = 0x4000 + offset * 8).
+///
+/// The record only holds the absorb mask, so the other element masks come from
+/// `elements`; its absorb mask is added to the record's.
+fn read_battle_actor(
+    addresses: &FF7Addresses,
+    index: u32,
+    name: String,
+    scene_id: u8,
+    limit: u16,
+    weapon_attack: u8,
+    elements: ElementDefense,
+) -> Result<BattleCharObj, String> {
+    let bytes = read_memory_buffer(battle_actor_address(addresses, index)?, BATTLE_ACTOR_LENGTH as usize)?;
+    let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+    let u32_at = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+
+    Ok(BattleCharObj {
+        index: index as u8,
+        name,
+        flags: bytes[0x05],
+        status: StatusFlags::from_bits_retain(u32_at(0x00)),
+        initial_status: StatusFlags::from_bits_retain(u32_at(0x44)),
+        hp: u32_at(0x2C),
+        max_hp: u32_at(0x30),
+        mp: u16_at(0x28),
+        max_mp: u16_at(0x2A),
+        atb: read_memory_short(battle_atb_address(addresses, index))?,
+        limit,
+        scene_id,
+        level: bytes[0x09],
+        character_id: bytes[0x0C],
+        strength: bytes[0x0D],
+        weapon_attack,
+        magic: bytes[0x0E],
+        evade: bytes[0x0F],
+        back_damage_multiplier: bytes[0x12],
+        dexterity: bytes[0x14],
+        luck: bytes[0x15],
+        last_covered: bytes[0x17],
+        last_targets: u16_at(0x18),
+        last_attacker: u16_at(0x1A),
+        defense: u16_at(0x20),
+        magic_defense: u16_at(0x22),
+        weak_elements: elements.weak,
+        resisted_elements: elements.resisted,
+        nullified_elements: elements.nullified,
+        absorbed_elements: ElementFlags::from_bits_retain(u16_at(0x26)) | elements.absorbed,
+        magic_evade: bytes[0x4D],
+        row: bytes[0x4E],
+        gil: u16_at(0x50),
+        exp: u32_at(0x54),
+    })
+}
+
+/// Weapon attack and element defense from a party member's equipment, by character id
+fn read_ally_equipment(addresses: &FF7Addresses, character_id: u8, kernel: &KernelBin) -> Result<BattleAllyEquipment, String> {
+    const CHAR_RECORD_LENGTH: u32 = 0x84;
+    let mut elements = ElementDefense::default();
+    if character_id >= 9 {
+        return Ok(BattleAllyEquipment::default());
+    }
+
+    let record_addr = addresses.character_records + character_id as u32 * CHAR_RECORD_LENGTH;
+    let weapon_id = read_memory_byte(record_addr + 0x1C)?;
+    let armor_id = read_memory_byte(record_addr + 0x1D)?;
+    let accessory_id = read_memory_byte(record_addr + 0x1E)?;
+    let weapon_attack = kernel.weapons.get(weapon_id as usize).map_or(0, |weapon| weapon.attack);
+    if let Some(armor) = kernel.armor.get(armor_id as usize) {
+        elements.add_equipment(armor.element_defense_mode, armor.element_defense);
+    }
+    if let Some(accessory) = kernel.accessories.get(accessory_id as usize) {
+        elements.add_equipment(accessory.element_defense_mode, accessory.element_defense);
+    }
+    Ok(BattleAllyEquipment { weapon_attack, elements })
+}
+
+fn read_element_rates(enemy_data_addr: u32) -> Result<Vec<Elemental>, String> {
+    let mut elements: Vec<Elemental> = Vec::new();
+    for i in 0..8 {
+        let element = read_memory_byte(enemy_data_addr + 0x28 + i)?;
+        elements.push(Elemental {
+            element,
+            effect: read_memory_byte(enemy_data_addr + 0x30 + i)?,
+        });
+    }
+    Ok(elements)
+}
+
+/// Equipment of the three party members, in party order. Only the equipped ids are read from
+/// memory, their data comes from `kernel`. Equipment can't change during a battle, so read it
+/// once per battle and pass it to `read_battle_allies`.
+pub fn read_battle_ally_equipment(addresses: &FF7Addresses, kernel: &KernelBin) -> Result<Vec<BattleAllyEquipment>, String> {
+    (0..3)
+        .map(|i| read_ally_equipment(addresses, read_memory_byte(addresses.party_member_ids + i)?, kernel))
+        .collect()
+}
+
+/// `equipment` comes from `read_battle_ally_equipment`; allies without an entry get none.
+pub fn read_battle_allies(addresses: &FF7Addresses, equipment: &[BattleAllyEquipment]) -> Result<Vec<BattleCharObj>, String> {
     let mut party_ids = Vec::new();
     for i in 0..3 {
         party_ids.push(read_memory_byte(addresses.party_member_ids + i)?);
@@ -26,56 +147,64 @@ pub fn read_battle_allies(addresses: &FF7Addresses) -> Result<Vec<BattleCharObj>
     }
 
     let mut chars: Vec<BattleCharObj> = Vec::new();
-    let char_obj_length = 104;
     for i in 0..3 {
         let name = party_names[i as usize].clone();
-        let char = BattleCharObj {
-            index: i as u8,
-            name,
-            status: read_memory_int(addresses.battle_char_base + i * char_obj_length)?,
-            flags: read_memory_byte(addresses.battle_char_base + i * char_obj_length + 0x5)?,
-            hp: read_memory_int(addresses.battle_char_base + i * char_obj_length + 0x2c)?,
-            max_hp: read_memory_int(addresses.battle_char_base + i * char_obj_length + 0x30)?,
-            mp: read_memory_short(addresses.battle_char_base + i * char_obj_length + 0x28)?,
-            max_mp: read_memory_short(addresses.battle_char_base + i * char_obj_length + 0x2a)?,
-            atb: read_memory_short(addresses.battle_atb_base + i * 68 + 0x2)?,
-            limit: read_memory_short(addresses.battle_char_array + i * 0x34 + 0x8)?,
-            scene_id: 0,
-        };
-        chars.push(char);
+        let limit = read_memory_short(addresses.battle_char_array + i * 0x34 + 0x8)?;
+        let equipment = equipment.get(i as usize).copied().unwrap_or_default();
+        chars.push(read_battle_actor(addresses, i, name, 0, limit, equipment.weapon_attack, equipment.elements)?);
     }
     Ok(chars)
 }
 
 pub fn read_battle_enemies(addresses: &FF7Addresses) -> Result<Vec<BattleCharObj>, String> {
     let mut chars: Vec<BattleCharObj> = Vec::new();
-    let char_obj_length = 104;
     let enemy_record_length = 16;
     let enemy_data_length = 184;
     for i in 4..10 {
         let enemy_scene_idx =
             read_memory_byte(addresses.enemy_obj_base + (i - 4) * enemy_record_length).unwrap_or(0);
-        let enemy_name =
-            read_name(addresses.enemy_data_base + u32::from(enemy_scene_idx) * enemy_data_length, 24);
-
-        let char = BattleCharObj {
-            index: i as u8,
-            name: enemy_name.unwrap_or_else(|_| String::from("???")),
-            status: read_memory_int(addresses.battle_char_base + i * char_obj_length)?,
-            flags: read_memory_byte(addresses.battle_char_base + i * char_obj_length + 0x5)?,
-            hp: read_memory_int(addresses.battle_char_base + i * char_obj_length + 0x2c)?,
-            max_hp: read_memory_int(addresses.battle_char_base + i * char_obj_length + 0x30)?,
-            mp: read_memory_short(addresses.battle_char_base + i * char_obj_length + 0x28)?,
-            max_mp: read_memory_short(addresses.battle_char_base + i * char_obj_length + 0x2a)?,
-            atb: read_memory_short(addresses.battle_atb_base + i * 68 + 0x2)?,
-            limit: 0,
-            scene_id: enemy_scene_idx,
-        };
-        chars.push(char);
+        let enemy_data_addr = addresses.enemy_data_base + u32::from(enemy_scene_idx) * enemy_data_length;
+        let name = read_name(enemy_data_addr, 24).unwrap_or_else(|_| String::from("???"));
+        let elements = ElementDefense::from_rates(&read_element_rates(enemy_data_addr)?);
+        chars.push(read_battle_actor(addresses, i, name, enemy_scene_idx, 0, 0, elements)?);
     }
     Ok(chars)
 }
 
+/// Actor indexes are 0-2 for allies and 4-9 for enemies, as in `BattleCharObj::index`.
+pub fn write_battle_actor_hp(addresses: &FF7Addresses, index: u32, hp: u32) -> Result<(), String> {
+    write_memory_int(battle_actor_address(addresses, index)? + 0x2C, hp)
+}
+
+pub fn write_battle_actor_mp(addresses: &FF7Addresses, index: u32, mp: u16) -> Result<(), String> {
+    write_memory_short(battle_actor_address(addresses, index)? + 0x28, mp)
+}
+
+pub fn write_battle_actor_status(addresses: &FF7Addresses, index: u32, status: StatusFlags) -> Result<(), String> {
+    write_memory_int(battle_actor_address(addresses, index)?, status.bits())
+}
+
+/// The ATB gauge is full at 0xFFFF
+pub fn write_battle_actor_atb(addresses: &FF7Addresses, index: u32, atb: u16) -> Result<(), String> {
+    battle_actor_address(addresses, index)?;
+    write_memory_short(battle_atb_address(addresses, index), atb)
+}
+
+/// Looks up the running battle in scene.bin and attaches the live enemy actors to
+/// the formation's entries.
+pub fn read_current_formation(addresses: &FF7Addresses, database: &SceneDatabase) -> Result<FormationDetails, String> {
+    let battle_id = read_memory_short(addresses.battle_id)?;
+    let mut details = database
+        .formation_details(battle_id)
+        .ok_or_else(|| format!("Battle id {} is not in scene.bin", battle_id))?;
+
+    let actors = read_battle_enemies(addresses)?;
+    for (enemy, actor) in details.enemies.iter_mut().zip(actors) {
+        enemy.actor = Some(actor);
+    }
+    Ok(details)
+}
+
 pub fn read_enemy_data(id: u32) -> Result<EnemyData, String> {
     let addresses = FF7Addresses::new();
     let enemy_data_length = 184;
@@ -97,16 +226,9 @@ pub fn read_enemy_data(id: u32) -> Result<EnemyData, String> {
     let ap = read_memory_short(enemy_data_addr + 0x9E)?;
     let back_damage_multiplier = read_memory_byte(enemy_data_addr + 0xA2)? / 8;
 
-    let mut elements: Vec<Elemental> = Vec::new();
-    for i in 0..8 {
-        let element = read_memory_byte(enemy_data_addr + 0x28 + i)?;
-        elements.push(Elemental {
-            element,
-            effect: read_memory_byte(enemy_data_addr + 0x30 + i)?,
-        });
-    }
+    let elements = read_element_rates(enemy_data_addr)?;
 
-    let status_immunities: u32 = read_memory_int(enemy_data_addr + 0xb0)?;
+    let status_immunities = StatusFlags::from_bits_retain(read_memory_int(enemy_data_addr + 0xb0)?);
 
     let item_names = crate::ff7::data::kernel::read_item_names(&addresses)?;
     let mut items: Vec<Item> = Vec::new();
@@ -176,8 +298,65 @@ pub fn read_enemy_attack_names(addresses: &FF7Addresses) -> Result<Vec<String>,
     Ok(attack_names)
 }
 
+const BATTLE_QUEUE_ENTRY_LENGTH: usize = 8;
+const BATTLE_QUEUE_ENTRIES: usize = 16;
+const SCENE_ATTACK_IDS_LENGTH: u32 = 0x40; // The scene's attack ids sit right before the attack names
+
+/// Decodes raw queue entries up to the first free slot (priority 0xFF). Names are left empty.
+pub fn parse_battle_queue(bytes: &[u8]) -> Vec<BattleQueuedAction> {
+    bytes
+        .chunks_exact(BATTLE_QUEUE_ENTRY_LENGTH)
+        .take_while(|entry| entry[0] != 0xFF)
+        .map(|entry| BattleQueuedAction {
+            priority: entry[0],
+            queue_position: entry[1],
+            actor_index: entry[2],
+            command_id: entry[3],
+            attack_id: u16::from_le_bytes([entry[4], entry[5]]),
+            target_mask: u16::from_le_bytes([entry[6], entry[7]]),
+            command_name: None,
+            attack_name: None,
+        })
+        .collect()
+}
+
+/// Reads the pending battle actions with command and attack names resolved.
+pub fn read_battle_queue(addresses: &FF7Addresses) -> Result<Vec<BattleQueuedAction>, String> {
+    let buffer = read_memory_buffer(addresses.battle_queue, BATTLE_QUEUE_ENTRIES * BATTLE_QUEUE_ENTRY_LENGTH)?;
+    let mut queue = parse_battle_queue(&buffer);
+    if queue.is_empty() {
+        return Ok(queue);
+    }
+
+    let command_names = read_command_names(addresses)?;
+    let attack_names = read_attack_names(addresses)?;
+    let item_names = read_item_names(addresses)?;
+    let enemy_attack_names = read_enemy_attack_names(addresses)?;
+    let enemy_attack_ids = read_memory_buffer(addresses.enemy_attack_names - SCENE_ATTACK_IDS_LENGTH, SCENE_ATTACK_IDS_LENGTH as usize)?;
+
+    let non_empty = |name: Option<&String>| name.filter(|name| !name.is_empty()).cloned();
+    for action in queue.iter_mut() {
+        action.command_name = non_empty(command_names.get(action.command_id as usize));
+        action.attack_name = match action.command_id {
+            // Item, Throw, W-Item
+            0x04 | 0x08 | 0x17 => non_empty(item_names.get(action.attack_id as usize)),
+            // Magic, Summon, E.Skill, Limit, W-Magic, W-Summon
+            0x02 | 0x03 | 0x0D | 0x14 | 0x15 | 0x16 => non_empty(attack_names.get(action.attack_id as usize)),
+            _ => enemy_attack_ids
+                .chunks_exact(2)
+                .position(|id| u16::from_le_bytes([id[0], id[1]]) == action.attack_id)
+                .and_then(|slot| non_empty(enemy_attack_names.get(slot))),
+        };
+    }
+    Ok(queue)
+}
+
 const BLOCK_HEADER_COUNT: usize = 16;
 const SCENE_SIZE: usize = 0x2000; // 8192
+const FORMATION_AI_SECTION_OFFSET: usize = 0x0C80;
+const ENEMY_AI_SECTION_OFFSET: usize = 0x0E80;
+const SCENE_DATA_SIZE: usize = 0x1E80;
+const SCENE_LOOKUP_TABLE_SIZE: usize = 64;
 
 /// Reads and parses the main scene.bin file from the game directory.
 pub fn read_scene_bin() -> Result<Vec<Scene>, String> {
@@ -192,7 +371,32 @@ pub fn read_scene_bin_from_path(scene_bin_path: &Path) -> Result<Vec<Scene>, Str
     let data = fs::read(scene_bin_path)
         .map_err(|e| format!("Failed to read scene.bin: {}", e))?;
 
-    let mut decompressed_scenes: Vec<Vec<u8>> = Vec::new();
+    let decompressed_scenes = split_scene_bin(&data);
+    let mut scenes: Vec<Scene> = Vec::new();
+    for (i, scene_data) in decompressed_scenes.into_iter().enumerate() {
+        let Some((compressed, scene_data)) = scene_data else {
+            continue;
+        };
+        match parse_scene_data(&scene_data, i as u16) {
+            Ok(scene) => scenes.push(Scene { compressed, ..scene }),
+            Err(e) => log::error!(target: "backend", "Failed to parse scene index {}: {}", i, e),
+        }
+    }
+
+    log::info!(target: "backend", "Successfully parsed {} scenes from scene.bin", scenes.len());
+    Ok(scenes) // Return the parsed scenes
+}
+
+/// Splits scene.bin into its blocks and returns the decompressed payload of every scene, in
+/// file order. Scenes that can't be decompressed are `None` so later scenes keep their index.
+pub fn unpack_scene_bin(data: &[u8]) -> Vec<Option<Vec<u8>>> {
+    split_scene_bin(data).into_iter().map(|scene| scene.map(|(_, decompressed)| decompressed)).collect()
+}
+
+/// Like `unpack_scene_bin`, but also returns the gzip data of every scene as stored, padded to
+/// 4 bytes, so unmodified scenes can be written back without recompressing them
+fn split_scene_bin(data: &[u8]) -> Vec<Option<(Vec<u8>, Vec<u8>)>> {
+    let mut decompressed_scenes: Vec<Option<(Vec<u8>, Vec<u8>)>> = Vec::new();
     let mut pos: usize = 0;
     let mut block: usize = 0;
     let data_len = data.len();
@@ -271,6 +475,7 @@ pub fn read_scene_bin_from_path(scene_bin_path: &Path) -> Result<Vec<Scene>, Str
 
             if current_scene_start + scene_size > data_len {
                 log::warn!(target: "backend", "Scene data range exceeds file bounds for block {}, scene index {}. Skipping.", block, index);
+                decompressed_scenes.push(None);
                 continue; // Skip this scene if its calculated range is invalid
             }
 
@@ -279,7 +484,10 @@ pub fn read_scene_bin_from_path(scene_bin_path: &Path) -> Result<Vec<Scene>, Str
             // Gzip decompression
             let mut decoder = GzDecoder::new(compressed_scene_data);
             let mut decompressed_data = Vec::new();
-            if let Err(e) = decoder.read_to_end(&mut decompressed_data) {
+            let read_result = decoder.read_to_end(&mut decompressed_data);
+            // The last scene of a block runs into the block's padding, keep only the gzip member
+            let compressed_len = (compressed_scene_data.len() - decoder.into_inner().len()).next_multiple_of(4);
+            if let Err(e) = read_result {
                  // Check for specific error: Gzip header mismatch (often indicates not Gzipped)
                  // The error kind might be io::ErrorKind::InvalidData
                  if e.kind() == std::io::ErrorKind::InvalidData && e.to_string().contains("invalid gzip header") {
@@ -290,13 +498,16 @@ pub fn read_scene_bin_from_path(scene_bin_path: &Path) -> Result<Vec<Scene>, Str
                  else {
                     log::error!(target: "backend", "Failed to decompress scene (block {}, index {}): {}. Skipping.", block, index, e);
                  }
+                decompressed_scenes.push(None);
                 continue; // Skip this scene on decompression error
             }
 
             if !decompressed_data.is_empty() {
-                decompressed_scenes.push(decompressed_data);
+                let compressed = compressed_scene_data[..compressed_len.min(compressed_scene_data.len())].to_vec();
+                decompressed_scenes.push(Some((compressed, decompressed_data)));
             } else {
                  log::warn!(target: "backend", "Decompressed scene (block {}, index {}) resulted in empty data. Original size: {}", block, index, compressed_scene_data.len());
+                 decompressed_scenes.push(None);
             }
         }
 
@@ -307,16 +518,7 @@ pub fn read_scene_bin_from_path(scene_bin_path: &Path) -> Result<Vec<Scene>, Str
     }
 
     log::info!(target: "backend", "Successfully decompressed {} scenes from scene.bin", decompressed_scenes.len());
-    let mut scenes: Vec<Scene> = Vec::new();
-    for (i, scene_data) in decompressed_scenes.iter().enumerate() {
-        match parse_scene_data(scene_data) {
-            Ok(scene) => scenes.push(scene),
-            Err(e) => log::error!(target: "backend", "Failed to parse scene index {}: {}", i, e),
-        }
-    }
-
-    log::info!(target: "backend", "Successfully parsed {} scenes from scene.bin", scenes.len());
-    Ok(scenes) // Return the parsed scenes
+    decompressed_scenes
 }
 
 fn read_string_from_bytes(bytes: &[u8]) -> String {
@@ -343,8 +545,8 @@ fn parse_battle_setup(cursor: &mut Cursor<&[u8]>) -> Result<SceneBattleSetup, St
     let _unused_align_ff = cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
     let mut next_battle_arena_candidates = [0u16; 4];
     cursor.read_u16_into::<LittleEndian>(&mut next_battle_arena_candidates).map_err(|e| e.to_string())?;
-    let flags = cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
-    let battle_layout_type = cursor.read_u8().map_err(|e| e.to_string())?;
+    let flags = BattleSetupFlags::from_bits_retain(cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?);
+    let battle_layout_type = BattleLayout::from(cursor.read_u8().map_err(|e| e.to_string())?);
     let pre_battle_camera_pos_index = cursor.read_u8().map_err(|e| e.to_string())?;
     Ok(SceneBattleSetup {
         battle_location,
@@ -381,8 +583,10 @@ fn parse_battle_formation_entry(cursor: &mut Cursor<&[u8]>) -> Result<SceneBattl
         pos_y: cursor.read_i16::<LittleEndian>().map_err(|e| e.to_string())?,
         pos_z: cursor.read_i16::<LittleEndian>().map_err(|e| e.to_string())?,
         row: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
-        cover_flags: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
-        initial_condition_flags: cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?,
+        cover_flags: CoverFlags::from_bits_retain(cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?),
+        initial_condition_flags: InitialConditionFlags::from_bits_retain(
+            cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?,
+        ),
     })
 }
 
@@ -409,8 +613,8 @@ fn parse_enemy_data(cursor: &mut Cursor<&[u8]>, enemy_id: u16) -> Result<SceneEn
         rate: element_effect_rates[i],
     });
 
-    let mut _action_animation_index = [0u8; 16];
-    cursor.read_exact(&mut _action_animation_index).map_err(|e| e.to_string())?;
+    let mut action_animation_indexes = [0u8; 16];
+    cursor.read_exact(&mut action_animation_indexes).map_err(|e| e.to_string())?;
 
     let mut enemy_attack_ids = [0u16; 16];
     cursor.read_u16_into::<LittleEndian>(&mut enemy_attack_ids).map_err(|e| e.to_string())?;
@@ -439,7 +643,7 @@ fn parse_enemy_data(cursor: &mut Cursor<&[u8]>, enemy_id: u16) -> Result<SceneEn
     let hp = cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
     let exp = cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
     let gil = cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
-    let status_immunities = cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let status_immunities = StatusFlags::from_bits_retain(cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?);
     let _unknown_b4 = cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
 
     Ok(SceneEnemy {
@@ -454,6 +658,7 @@ fn parse_enemy_data(cursor: &mut Cursor<&[u8]>, enemy_id: u16) -> Result<SceneEn
         magic,
         magic_defense,
         element_rates,
+        action_animation_indexes,
         enemy_attack_ids,
         enemy_attack_camera_movement_ids,
         items,
@@ -469,14 +674,89 @@ fn parse_enemy_data(cursor: &mut Cursor<&[u8]>, enemy_id: u16) -> Result<SceneEn
     })
 }
 
-fn parse_attack(cursor: &mut Cursor<&[u8]>, attack_id: u16) -> Result<SceneAttack, String> {
+fn parse_attack(cursor: &mut Cursor<&[u8]>, attack_id: u16, data: AttackData) -> Result<SceneAttack, String> {
     let mut raw_name = [0u8; 32];
     cursor.read_exact(&mut raw_name).map_err(|e| e.to_string())?;
     let name = read_string_from_bytes(&raw_name);
-    Ok(SceneAttack { id: attack_id, name })
+    Ok(SceneAttack { id: attack_id, name, data })
 }
 
-fn parse_scene_data(data: &[u8]) -> Result<Scene, String> {
+/// Parses an AI section: `N` u16 offsets (relative to the section start) to per-owner script blocks.
+/// Each block starts with a header of 16 event script offsets relative to the block itself.
+/// A malformed block is logged and left as `None` so the rest of the scene still loads.
+fn parse_ai_section<const N: usize>(section: &[u8]) -> [Option<SceneAIData>; N] {
+    let mut ai: [Option<SceneAIData>; N] = std::array::from_fn(|_| None);
+    let mut cursor = Cursor::new(section);
+    let mut owner_offsets = [0u16; N];
+    if let Err(e) = cursor.read_u16_into::<LittleEndian>(&mut owner_offsets) {
+        log::warn!(target: "backend", "Failed to read AI section header: {}", e);
+        return ai;
+    }
+
+    for (owner, &offset) in owner_offsets.iter().enumerate() {
+        if offset == 0xFFFF {
+            continue;
+        }
+        // An owner's block runs until the next owner's block or the end of the section
+        let start = offset as usize;
+        let end = owner_offsets
+            .iter()
+            .filter(|&&o| o != 0xFFFF && o as usize > start)
+            .map(|&o| o as usize)
+            .min()
+            .unwrap_or(section.len());
+        if start + 32 > end || end > section.len() {
+            log::warn!(target: "backend", "AI block {} at offset 0x{:X} is out of bounds, skipping it", owner, start);
+            continue;
+        }
+        match parse_ai_block(&section[start..end]) {
+            Ok(block) => ai[owner] = Some(block),
+            Err(e) => log::warn!(target: "backend", "Failed to parse AI block {}: {}", owner, e),
+        }
+    }
+    ai
+}
+
+fn parse_ai_block(block: &[u8]) -> Result<SceneAIData, String> {
+    let mut cursor = Cursor::new(block);
+    let mut script_offsets = [0u16; 16];
+    cursor.read_u16_into::<LittleEndian>(&mut script_offsets).map_err(|e| e.to_string())?;
+
+    let mut scripts = Vec::new();
+    for (event_idx, &offset) in script_offsets.iter().enumerate() {
+        if offset == 0xFFFF {
+            continue;
+        }
+        let start = offset as usize;
+        let next = script_offsets
+            .iter()
+            .filter(|&&o| o != 0xFFFF && o as usize > start)
+            .map(|&o| o as usize)
+            .min();
+        let mut end = next.unwrap_or(block.len()).min(block.len());
+        if start > end {
+            return Err(format!("AI script {} at offset 0x{:X} is out of bounds", event_idx, start));
+        }
+        if next.is_none() {
+            // The last script of a block is followed by 0xFF padding
+            while end > start && block[end - 1] == 0xFF {
+                end -= 1;
+            }
+        }
+        scripts.push(SceneAIScript {
+            event: SceneAIEvent::ALL[event_idx],
+            offset,
+            bytecode: block[start..end].to_vec(),
+        });
+    }
+
+    Ok(SceneAIData {
+        header: SceneAIHeader { script_offsets },
+        scripts,
+    })
+}
+
+fn parse_scene_data(data: &[u8], id: u16) -> Result<Scene, String> {
     let mut cursor = Cursor::new(data);
 
     let enemy_id_1 = cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
@@ -490,8 +770,8 @@ fn parse_scene_data(data: &[u8]) -> Result<Scene, String> {
             next_formation_id_on_win: 0, 
             escape_counter: 0, 
             next_battle_arena_candidates: [0; 4], 
-            flags: 0, 
-            battle_layout_type: 0, 
+            flags: BattleSetupFlags::empty(), 
+            battle_layout_type: BattleLayout::Normal, 
             pre_battle_camera_pos_index: 0 
         },
         camera_placement: SceneCameraPlacement {
@@ -501,7 +781,7 @@ fn parse_scene_data(data: &[u8]) -> Result<Scene, String> {
                 SceneCameraPosition { x_pos: 0, y_pos: 0, z_pos: 0, x_dir: 0, y_dir: 0, z_dir: 0 },
             ],
         },
-        enemies: Vec::new(),
+        enemies: std::array::from_fn(|_| None),
     });
 
     for i in 0..4 {
@@ -513,10 +793,10 @@ fn parse_scene_data(data: &[u8]) -> Result<Scene, String> {
     }
 
     for i in 0..4 {
-        for _ in 0..6 {
+        for slot in 0..6 {
             let enemy = parse_battle_formation_entry(&mut cursor)?;
             if enemy.enemy_id != 0xFFFF {
-                formations[i].enemies.push(enemy);
+                formations[i].enemies[slot] = Some(enemy);
             }
         }
     }
@@ -526,8 +806,12 @@ fn parse_scene_data(data: &[u8]) -> Result<Scene, String> {
     enemies[1] = parse_enemy_data(&mut cursor, enemy_id_2)?;
     enemies[2] = parse_enemy_data(&mut cursor, enemy_id_3)?;
 
-    // Skip attack data
-    cursor.seek(SeekFrom::Current(32 * 28)).map_err(|e| e.to_string())?;
+    let mut attack_data = [AttackData::default(); 32];
+    for data in attack_data.iter_mut() {
+        let mut raw = [0u8; 28];
+        cursor.read_exact(&mut raw).map_err(|e| e.to_string())?;
+        *data = parse_attack_data(&raw)?;
+    }
 
     let mut attack_ids = [0u16; 32];
     cursor.read_u16_into::<LittleEndian>(&mut attack_ids).map_err(|e| e.to_string())?;
@@ -535,20 +819,33 @@ fn parse_scene_data(data: &[u8]) -> Result<Scene, String> {
     let mut attacks = Vec::new();
     for i in 0..32 {
         if attack_ids[i] != 0xFFFF {
-            attacks.push(parse_attack(&mut cursor, attack_ids[i])?);
+            attacks.push(parse_attack(&mut cursor, attack_ids[i], attack_data[i])?);
         } else {
             // Skip the name data for empty attacks
             cursor.seek(SeekFrom::Current(32)).map_err(|e| e.to_string())?;
         }
     }
 
-    // Skip AI data
-    cursor.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+    let formation_ai_start = cursor.position() as usize;
+    let (formation_ai, enemy_ai) = if data.len() < ENEMY_AI_SECTION_OFFSET || formation_ai_start > ENEMY_AI_SECTION_OFFSET {
+        log::warn!(target: "backend", "Scene data is too short for its AI sections: {} bytes", data.len());
+        (std::array::from_fn(|_| None), std::array::from_fn(|_| None))
+    } else {
+        (
+            parse_ai_section::<4>(&data[formation_ai_start..ENEMY_AI_SECTION_OFFSET]),
+            parse_ai_section::<3>(&data[ENEMY_AI_SECTION_OFFSET..]),
+        )
+    };
 
     Ok(Scene {
+        id,
         enemies,
         formations,
         attacks,
+        formation_ai,
+        enemy_ai,
+        raw: data.to_vec(),
+        compressed: Vec::new(),
     })
 }
 
@@ -560,11 +857,12 @@ impl Default for SceneEnemy {
             name: String::new(),
             level: 0, speed: 0, luck: 0, evade: 0, strength: 0, defense: 0, magic: 0, magic_defense: 0,
             element_rates: std::array::from_fn(|_| SceneElementRate { element_type: 0xFF, rate: 0xFF }),
+            action_animation_indexes: [0xFF; 16],
             enemy_attack_ids: [0xFFFF; 16],
             enemy_attack_camera_movement_ids: [0xFFFF; 16],
             items: std::array::from_fn(|_| SceneEnemyItem { rate: 0, item_id: 0xFFFF }),
             manipulated_berserk_attack_indexes: [0xFFFF; 3],
-            mp: 0, ap: 0, morph_item_id: 0xFFFF, back_damage_multiplier: 0, hp: 0, exp: 0, gil: 0, status_immunities: 0xFFFFFFFF,
+            mp: 0, ap: 0, morph_item_id: 0xFFFF, back_damage_multiplier: 0, hp: 0, exp: 0, gil: 0, status_immunities: StatusFlags::all(),
         }
     }
 }
@@ -574,7 +872,353 @@ impl Default for SceneBattleFormationEntry {
         SceneBattleFormationEntry {
             enemy_id: 0xFFFF,
             pos_x: 0, pos_y: 0, pos_z: 0,
-            row: 0, cover_flags: 0, initial_condition_flags: 0,
+            row: 0, cover_flags: CoverFlags::empty(), initial_condition_flags: InitialConditionFlags::empty(),
+        }
+    }
+}
+
+// --- Writers ---
+// Encoders write over the original bytes of a record and leave padding and unknown fields untouched.
+
+const BATTLE_SETUP_OFFSET: usize = 0x0008;
+const CAMERA_PLACEMENT_OFFSET: usize = 0x0058;
+const FORMATION_ENTRY_OFFSET: usize = 0x0118;
+const ENEMY_DATA_OFFSET: usize = 0x0298;
+const ATTACK_DATA_OFFSET: usize = 0x04C0;
+const ATTACK_ID_OFFSET: usize = 0x0840;
+const ATTACK_NAME_OFFSET: usize = 0x0880;
+const BATTLE_SETUP_SIZE: usize = 20;
+const CAMERA_PLACEMENT_SIZE: usize = 48;
+const FORMATION_ENTRY_SIZE: usize = 16;
+const ENEMY_DATA_SIZE: usize = 184;
+const ATTACK_DATA_SIZE: usize = 28;
+const NAME_SIZE: usize = 32;
+
+/// Keeps the original name bytes (including anything after the terminator) unless the name changed.
+fn encode_name(name: &str, bytes: &mut [u8]) -> Result<(), String> {
+    if read_string_from_bytes(bytes) == name {
+        return Ok(());
+    }
+    let encoded = encode_text(name)?;
+    if encoded.len() >= bytes.len() {
+        return Err(format!("Name \"{}\" is longer than {} characters", name, bytes.len() - 1));
+    }
+    bytes.fill(0xFF);
+    bytes[..encoded.len()].copy_from_slice(&encoded);
+    Ok(())
+}
+
+fn encode_camera_position(position: &SceneCameraPosition, bytes: &mut [u8]) {
+    let values = [position.x_pos, position.y_pos, position.z_pos, position.x_dir, position.y_dir, position.z_dir];
+    for (value, chunk) in values.iter().zip(bytes.chunks_exact_mut(2)) {
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn encode_battle_setup(setup: &SceneBattleSetup, bytes: &mut [u8]) {
+    bytes[0x00..0x02].copy_from_slice(&setup.battle_location.to_le_bytes());
+    bytes[0x02..0x04].copy_from_slice(&setup.next_formation_id_on_win.to_le_bytes());
+    bytes[0x04..0x06].copy_from_slice(&setup.escape_counter.to_le_bytes());
+    for (candidate, chunk) in setup.next_battle_arena_candidates.iter().zip(bytes[0x08..0x10].chunks_exact_mut(2)) {
+        chunk.copy_from_slice(&candidate.to_le_bytes());
+    }
+    bytes[0x10..0x12].copy_from_slice(&setup.flags.bits().to_le_bytes());
+    bytes[0x12] = setup.battle_layout_type.into();
+    bytes[0x13] = setup.pre_battle_camera_pos_index;
+}
+
+fn encode_camera_placement(placement: &SceneCameraPlacement, bytes: &mut [u8]) {
+    encode_camera_position(&placement.primary_idle_camera, &mut bytes[0..12]);
+    for (position, chunk) in placement.other_camera_positions.iter().zip(bytes[12..36].chunks_exact_mut(12)) {
+        encode_camera_position(position, chunk);
+    }
+}
+
+fn encode_battle_formation_entry(entry: &SceneBattleFormationEntry, bytes: &mut [u8]) {
+    bytes[0x00..0x02].copy_from_slice(&entry.enemy_id.to_le_bytes());
+    bytes[0x02..0x04].copy_from_slice(&entry.pos_x.to_le_bytes());
+    bytes[0x04..0x06].copy_from_slice(&entry.pos_y.to_le_bytes());
+    bytes[0x06..0x08].copy_from_slice(&entry.pos_z.to_le_bytes());
+    bytes[0x08..0x0A].copy_from_slice(&entry.row.to_le_bytes());
+    bytes[0x0A..0x0C].copy_from_slice(&entry.cover_flags.bits().to_le_bytes());
+    bytes[0x0C..0x10].copy_from_slice(&entry.initial_condition_flags.bits().to_le_bytes());
+}
+
+fn encode_enemy_data(enemy: &SceneEnemy, bytes: &mut [u8]) -> Result<(), String> {
+    encode_name(&enemy.name, &mut bytes[0x00..0x20])?;
+    bytes[0x20..0x28].copy_from_slice(&[
+        enemy.level,
+        enemy.speed,
+        enemy.luck,
+        enemy.evade,
+        enemy.strength,
+        enemy.defense,
+        enemy.magic,
+        enemy.magic_defense,
+    ]);
+    for (i, rate) in enemy.element_rates.iter().enumerate() {
+        bytes[0x28 + i] = rate.element_type;
+        bytes[0x30 + i] = rate.rate;
+    }
+    bytes[0x38..0x48].copy_from_slice(&enemy.action_animation_indexes);
+    let ids = enemy.enemy_attack_ids.iter().chain(enemy.enemy_attack_camera_movement_ids.iter());
+    for (id, chunk) in ids.zip(bytes[0x48..0x88].chunks_exact_mut(2)) {
+        chunk.copy_from_slice(&id.to_le_bytes());
+    }
+    for (i, item) in enemy.items.iter().enumerate() {
+        bytes[0x88 + i] = item.rate;
+        bytes[0x8C + i * 2..0x8E + i * 2].copy_from_slice(&item.item_id.to_le_bytes());
+    }
+    for (index, chunk) in enemy.manipulated_berserk_attack_indexes.iter().zip(bytes[0x94..0x9A].chunks_exact_mut(2)) {
+        chunk.copy_from_slice(&index.to_le_bytes());
+    }
+    bytes[0x9C..0x9E].copy_from_slice(&enemy.mp.to_le_bytes());
+    bytes[0x9E..0xA0].copy_from_slice(&enemy.ap.to_le_bytes());
+    bytes[0xA0..0xA2].copy_from_slice(&enemy.morph_item_id.to_le_bytes());
+    bytes[0xA2] = enemy.back_damage_multiplier;
+    bytes[0xA4..0xA8].copy_from_slice(&enemy.hp.to_le_bytes());
+    bytes[0xA8..0xAC].copy_from_slice(&enemy.exp.to_le_bytes());
+    bytes[0xAC..0xB0].copy_from_slice(&enemy.gil.to_le_bytes());
+    bytes[0xB0..0xB4].copy_from_slice(&enemy.status_immunities.bits().to_le_bytes());
+    Ok(())
+}
+
+/// Lays out the scripts of one AI block after its 16-entry header. Events that shared
+/// a script in the original header keep sharing it.
+fn encode_ai_block(ai: &SceneAIData) -> Vec<u8> {
+    let mut scripts: Vec<&SceneAIScript> = ai.scripts.iter().collect();
+    scripts.sort_by_key(|script| script.offset);
+
+    let mut script_offsets = [0xFFFFu16; 16];
+    let mut body = Vec::new();
+    let mut placed: Vec<(u16, u16)> = Vec::new(); // (original offset, new offset)
+    for script in scripts {
+        let offset = match placed.iter().find(|(original, _)| *original == script.offset) {
+            Some(&(_, offset)) => offset,
+            None => {
+                let offset = (32 + body.len()) as u16;
+                body.extend_from_slice(&script.bytecode);
+                placed.push((script.offset, offset));
+                offset
+            }
+        };
+        script_offsets[script.event as usize] = offset;
+    }
+
+    let mut block = Vec::with_capacity(32 + body.len());
+    for offset in script_offsets {
+        block.extend_from_slice(&offset.to_le_bytes());
+    }
+    block.extend(body);
+    block
+}
+
+/// Builds an AI section of `size` bytes: one u16 offset per owner, followed by the owners' blocks.
+fn encode_ai_section(ai: &[Option<SceneAIData>], size: usize) -> Result<Vec<u8>, String> {
+    let mut section = vec![0xFFu8; ai.len() * 2];
+    for (owner, data) in ai.iter().enumerate() {
+        if let Some(data) = data {
+            if !section.len().is_multiple_of(2) {
+                section.push(0xFF);
+            }
+            let offset = section.len() as u16;
+            section[owner * 2..owner * 2 + 2].copy_from_slice(&offset.to_le_bytes());
+            section.extend(encode_ai_block(data));
+        }
+    }
+    if section.len() > size {
+        return Err(format!("AI scripts need {} bytes but only {} are available", section.len(), size));
+    }
+    section.resize(size, 0xFF);
+    Ok(section)
+}
+
+/// Writes an AI section over `bytes`. The original layout is kept as long as the scripts
+/// still match what it decodes to; otherwise the section is laid out from scratch.
+fn encode_ai_section_over<const N: usize>(ai: &[Option<SceneAIData>; N], bytes: &mut [u8]) -> Result<(), String> {
+    let encoded = encode_ai_section(ai, bytes.len())?;
+    let unchanged = encode_ai_section(&parse_ai_section::<N>(bytes), bytes.len())
+        .is_ok_and(|original| original == encoded);
+    if !unchanged {
+        bytes.copy_from_slice(&encoded);
+    }
+    Ok(())
+}
+
+/// Assigns attacks to their slots: the n-th attack goes back into the n-th slot that held one,
+/// new attacks fill the free slots in order.
+fn attack_slots(scene: &Scene, raw_ids: &[u16; 32]) -> Result<[Option<usize>; 32], String> {
+    let mut slots = [None; 32];
+    let used = (0..32).filter(|&slot| raw_ids[slot] != 0xFFFF);
+    let free = (0..32).filter(|&slot| raw_ids[slot] == 0xFFFF);
+    let mut order = used.chain(free);
+    for attack in 0..scene.attacks.len() {
+        let slot = order
+            .next()
+            .ok_or_else(|| format!("A scene holds at most 32 attacks, found {}", scene.attacks.len()))?;
+        slots[slot] = Some(attack);
+    }
+    Ok(slots)
+}
+
+/// Serializes a scene back into its decompressed 7808-byte layout, on top of the bytes it was
+/// parsed from. An unmodified scene is reproduced byte for byte.
+pub fn encode_scene_data(scene: &Scene) -> Result<Vec<u8>, String> {
+    let mut out = if scene.raw.len() == SCENE_DATA_SIZE {
+        scene.raw.clone()
+    } else {
+        vec![0xFF; SCENE_DATA_SIZE]
+    };
+
+    for (enemy, chunk) in scene.enemies.iter().zip(out[0x00..0x06].chunks_exact_mut(2)) {
+        chunk.copy_from_slice(&enemy.id.to_le_bytes());
+    }
+
+    for (i, formation) in scene.formations.iter().enumerate() {
+        let setup = BATTLE_SETUP_OFFSET + i * BATTLE_SETUP_SIZE;
+        encode_battle_setup(&formation.setup, &mut out[setup..setup + BATTLE_SETUP_SIZE]);
+        let camera = CAMERA_PLACEMENT_OFFSET + i * CAMERA_PLACEMENT_SIZE;
+        encode_camera_placement(&formation.camera_placement, &mut out[camera..camera + CAMERA_PLACEMENT_SIZE]);
+
+        for (slot, entry) in formation.enemies.iter().enumerate() {
+            let start = FORMATION_ENTRY_OFFSET + (i * 6 + slot) * FORMATION_ENTRY_SIZE;
+            let bytes = &mut out[start..start + FORMATION_ENTRY_SIZE];
+            match entry {
+                Some(entry) => encode_battle_formation_entry(entry, bytes),
+                // Empty slots keep their original bytes unless they held an enemy that was removed
+                None if bytes[0..2] != [0xFF, 0xFF] => bytes.fill(0xFF),
+                None => {}
+            }
+        }
+    }
+
+    for (i, enemy) in scene.enemies.iter().enumerate() {
+        let start = ENEMY_DATA_OFFSET + i * ENEMY_DATA_SIZE;
+        encode_enemy_data(enemy, &mut out[start..start + ENEMY_DATA_SIZE])?;
+    }
+
+    let mut raw_ids = [0u16; 32];
+    for (id, chunk) in raw_ids.iter_mut().zip(out[ATTACK_ID_OFFSET..ATTACK_NAME_OFFSET].chunks_exact(2)) {
+        *id = u16::from_le_bytes([chunk[0], chunk[1]]);
+    }
+    for (slot, attack) in attack_slots(scene, &raw_ids)?.iter().enumerate() {
+        let data = ATTACK_DATA_OFFSET + slot * ATTACK_DATA_SIZE;
+        let id = ATTACK_ID_OFFSET + slot * 2;
+        let name = ATTACK_NAME_OFFSET + slot * NAME_SIZE;
+        match attack.map(|index| &scene.attacks[index]) {
+            Some(attack) => {
+                if raw_ids[slot] == 0xFFFF {
+                    out[data..data + ATTACK_DATA_SIZE].fill(0xFF);
+                }
+                encode_attack_data(&attack.data, &mut out[data..data + ATTACK_DATA_SIZE]);
+                out[id..id + 2].copy_from_slice(&attack.id.to_le_bytes());
+                encode_name(&attack.name, &mut out[name..name + NAME_SIZE])?;
+            }
+            None if raw_ids[slot] != 0xFFFF => {
+                out[data..data + ATTACK_DATA_SIZE].fill(0xFF);
+                out[id..id + 2].copy_from_slice(&[0xFF, 0xFF]);
+                out[name..name + NAME_SIZE].fill(0xFF);
+            }
+            None => {}
+        }
+    }
+
+    encode_ai_section_over(&scene.formation_ai, &mut out[FORMATION_AI_SECTION_OFFSET..ENEMY_AI_SECTION_OFFSET])?;
+    encode_ai_section_over(&scene.enemy_ai, &mut out[ENEMY_AI_SECTION_OFFSET..SCENE_DATA_SIZE])?;
+    Ok(out)
+}
+
+/// Gzip data padded with 0xFF to a multiple of 4 bytes, since block offsets are in 4-byte units
+fn gzip_scene(data: &[u8]) -> Result<Vec<u8>, String> {
+    // Same header as the original file: no timestamp, Unix OS byte
+    let mut encoder = GzBuilder::new().operating_system(3).write(Vec::new(), Compression::best());
+    encoder.write_all(data).map_err(|e| e.to_string())?;
+    let mut compressed = encoder.finish().map_err(|e| e.to_string())?;
+    while !compressed.len().is_multiple_of(4) {
+        compressed.push(0xFF);
+    }
+    Ok(compressed)
+}
+
+/// Writes the offset header into a block, pads it to 8 KiB and resets the builder state.
+fn finish_scene_block(block: &mut Vec<u8>, block_offsets: &mut Vec<u32>) -> Vec<u8> {
+    for i in 0..BLOCK_HEADER_COUNT {
+        let offset = block_offsets.get(i).copied().unwrap_or(0xFFFFFFFF);
+        block[i * 4..i * 4 + 4].copy_from_slice(&offset.to_le_bytes());
+    }
+    block.resize(SCENE_SIZE, 0xFF);
+    block_offsets.clear();
+    std::mem::take(block)
+}
+
+/// Unmodified scenes reuse the gzip data they were read from, so an untouched scene.bin is
+/// written back byte for byte
+fn compress_scene(scene: &Scene) -> Result<Vec<u8>, String> {
+    let data = encode_scene_data(scene)?;
+    if !scene.compressed.is_empty() && data == scene.raw {
+        return Ok(scene.compressed.clone());
+    }
+    gzip_scene(&data)
+}
+
+/// Compresses every scene and packs them into 8 KiB blocks, each starting with 16 u32 offsets
+/// (in 4-byte units, 0xFFFFFFFF for unused entries). Returns the packed file and the regenerated
+/// 64-byte scene lookup table (first scene index of every block, 0xFF for unused blocks), which
+/// belongs in `BattleGrowthData::scene_lookup_table` of kernel.bin.
+///
+/// Scenes are written at their `id`, so they have to be sorted by id. Missing ids, e.g. in a
+/// filtered list or for scenes that failed to load, are filled with blank scenes (every byte
+/// 0xFF) so the scenes after them keep their battle ids.
+pub fn pack_scene_bin(scenes: &[Scene]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mut out = Vec::new();
+    let mut lookup_table = Vec::with_capacity(SCENE_LOOKUP_TABLE_SIZE);
+    let mut block: Vec<u8> = Vec::new();
+    let mut block_offsets: Vec<u32> = Vec::new();
+
+    if let Some(pair) = scenes.windows(2).find(|pair| pair[1].id <= pair[0].id) {
+        return Err(format!("Scene {} comes after scene {}; scenes must be sorted by id", pair[1].id, pair[0].id));
+    }
+    // The lookup table stores scene indexes as bytes
+    if let Some(scene) = scenes.iter().find(|scene| scene.id > u8::MAX as u16) {
+        return Err(format!("scene.bin can hold at most {} scenes, got id {}", u8::MAX as usize + 1, scene.id));
+    }
+
+    let scene_count = scenes.last().map_or(0, |scene| scene.id as usize + 1);
+    let mut remaining = scenes.iter().peekable();
+    for index in 0..scene_count {
+        let compressed = match remaining.next_if(|scene| scene.id as usize == index) {
+            Some(scene) => compress_scene(scene)?,
+            None => gzip_scene(&[0xFF; SCENE_DATA_SIZE])?,
+        };
+        if BLOCK_HEADER_COUNT * 4 + compressed.len() > SCENE_SIZE {
+            return Err(format!("Scene {} does not fit in a block ({} bytes compressed)", index, compressed.len()));
         }
+
+        if block_offsets.len() == BLOCK_HEADER_COUNT || block.len() + compressed.len() > SCENE_SIZE {
+            out.extend(finish_scene_block(&mut block, &mut block_offsets));
+        }
+        if block_offsets.is_empty() {
+            if lookup_table.len() == SCENE_LOOKUP_TABLE_SIZE {
+                return Err(format!("scene.bin can hold at most {} blocks", SCENE_LOOKUP_TABLE_SIZE));
+            }
+            lookup_table.push(index as u8);
+            block.resize(BLOCK_HEADER_COUNT * 4, 0xFF);
+        }
+        block_offsets.push((block.len() / 4) as u32);
+        block.extend(compressed);
+    }
+    if !block_offsets.is_empty() {
+        out.extend(finish_scene_block(&mut block, &mut block_offsets));
     }
+
+    lookup_table.resize(SCENE_LOOKUP_TABLE_SIZE, 0xFF);
+    Ok((out, lookup_table))
+}
+
+/// Writes scene.bin and returns the regenerated scene lookup table, which has to be stored
+/// in kernel.bin as well when the number of scenes per block changes.
+pub fn write_scene_bin(scenes: &[Scene], scene_bin_path: &Path) -> Result<Vec<u8>, String> {
+    let (data, lookup_table) = pack_scene_bin(scenes)?;
+    fs::write(scene_bin_path, data).map_err(|e| format!("Failed to write scene.bin: {}", e))?;
+    Ok(lookup_table)
 }
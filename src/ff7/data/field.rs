@@ -1,6 +1,9 @@
 use crate::ff7::addresses::FF7Addresses;
 use crate::ff7::types::field::{FieldData, FieldModel, FieldLineObj, FieldLights, Light, FieldEncounterTables, FieldEncounterSet, EncounterPair};
+use crate::utils::lzs::lzs_decompress;
 use crate::utils::memory::*;
+use std::fs;
+use std::path::Path;
 
 fn read_f32_from_memory(address: u32) -> Result<f32, String> {
     let int_val = read_memory_int(address)?;
@@ -258,38 +261,66 @@ fn decode_encounter_pair(packed: u16) -> EncounterPair {
     }
 }
 
+const ENCOUNTER_SET_SIZE: usize = 24;
+const FIELD_SECTION_COUNT: usize = 9;
+const FIELD_ENCOUNTER_SECTION: usize = 6;
+
+/// Parses one 24-byte encounter table from field section 7
+pub fn parse_encounter_set(bytes: &[u8]) -> Result<FieldEncounterSet, String> {
+    if bytes.len() < ENCOUNTER_SET_SIZE {
+        return Err(format!("Encounter table needs {} bytes, got {}", ENCOUNTER_SET_SIZE, bytes.len()));
+    }
+    let pair_at = |offset: usize| decode_encounter_pair(u16::from_le_bytes([bytes[offset], bytes[offset + 1]]));
+
+    Ok(FieldEncounterSet {
+        active: bytes[0] != 0,
+        encounter_rate: bytes[1],
+        normal_encounters: (0..6).map(|i| pair_at(2 + i * 2)).collect(),
+        back_attacks: (0..2).map(|i| pair_at(14 + i * 2)).collect(),
+        side_attack: pair_at(18),
+        pincer_attack: pair_at(20),
+    })
+}
+
 fn read_encounter_set(base_address: u32) -> Result<FieldEncounterSet, String> {
-    let enabled = read_memory_byte(base_address)?;
-    let encounter_rate = read_memory_byte(base_address + 1)?;
-    
-    let mut normal_encounters = Vec::new();
-    for i in 0..6 {
-        let packed = read_memory_short(base_address + 2 + i * 2)?;
-        normal_encounters.push(decode_encounter_pair(packed));
+    parse_encounter_set(&read_memory_buffer(base_address, ENCOUNTER_SET_SIZE)?)
+}
+
+/// Reads both encounter tables from a field file, either LZS-compressed as stored in
+/// flevel.lgp or already decompressed.
+pub fn parse_field_file_encounters(data: &[u8]) -> Result<FieldEncounterTables, String> {
+    let compressed = data.len() >= 4 && u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize == data.len() - 4;
+    let decompressed;
+    let field = if compressed {
+        decompressed = lzs_decompress(data)?;
+        &decompressed[..]
+    } else {
+        data
+    };
+
+    let header_size = 6 + FIELD_SECTION_COUNT * 4;
+    if field.len() < header_size {
+        return Err("Field file is too short for its section header".to_string());
     }
-    
-    let mut back_attacks = Vec::new();
-    for i in 0..2 {
-        let packed = read_memory_short(base_address + 14 + i * 2)?;
-        back_attacks.push(decode_encounter_pair(packed));
+    let offset_at = 6 + FIELD_ENCOUNTER_SECTION * 4;
+    let section = u32::from_le_bytes([field[offset_at], field[offset_at + 1], field[offset_at + 2], field[offset_at + 3]]) as usize;
+    let start = section + 4; // Skip the section length
+    let end = start + ENCOUNTER_SET_SIZE * 2;
+    if end > field.len() {
+        return Err(format!("Encounter section at 0x{:X} is outside the field file", section));
     }
-    
-    let side_attack_packed = read_memory_short(base_address + 18)?;
-    let side_attack = decode_encounter_pair(side_attack_packed);
-    
-    let pincer_attack_packed = read_memory_short(base_address + 20)?;
-    let pincer_attack = decode_encounter_pair(pincer_attack_packed);
-    
-    Ok(FieldEncounterSet {
-        active: enabled != 0,
-        encounter_rate,
-        normal_encounters,
-        back_attacks,
-        side_attack,
-        pincer_attack,
+
+    Ok(FieldEncounterTables {
+        table1: parse_encounter_set(&field[start..start + ENCOUNTER_SET_SIZE])?,
+        table2: parse_encounter_set(&field[start + ENCOUNTER_SET_SIZE..end])?,
     })
 }
 
+pub fn read_field_file_encounters(path: &Path) -> Result<FieldEncounterTables, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read field file {}: {}", path.display(), e))?;
+    parse_field_file_encounters(&data)
+}
+
 fn empty_encounter_set() -> FieldEncounterSet {
     FieldEncounterSet {
         active: false,
@@ -310,8 +341,7 @@ pub fn read_field_encounters(addresses: &FF7Addresses) -> Result<FieldEncounterT
         });
     }
     
-    let section7_offset = read_memory_int(addresses.field_section_offsets + 6 * 4)?;
-    let encounter_data_addr = field_data_ptr + 4 + section7_offset;
+    let encounter_data_addr = encounter_section_address(addresses)?;
     
     let table1 = read_encounter_set(encounter_data_addr)?;
     let table2 = read_encounter_set(encounter_data_addr + 24)?;
@@ -321,3 +351,71 @@ pub fn read_field_encounters(addresses: &FF7Addresses) -> Result<FieldEncounterT
         table2,
     })
 }
+
+const NORMAL_RATE_TOTAL: u32 = 64;
+
+fn encode_encounter_pair(pair: &EncounterPair) -> Result<u16, String> {
+    if pair.encounter_id > 0x3FF {
+        return Err(format!("Encounter id {} does not fit in 10 bits", pair.encounter_id));
+    }
+    if pair.rate > 0x3F {
+        return Err(format!("Encounter rate {} does not fit in 6 bits", pair.rate));
+    }
+    Ok((pair.rate as u16) << 10 | pair.encounter_id)
+}
+
+/// Packs a table into the first 22 bytes of its 24-byte slot; the last two bytes are padding.
+/// Active tables must have normal encounter rates that add up to 64.
+pub fn encode_encounter_set(set: &FieldEncounterSet) -> Result<Vec<u8>, String> {
+    if set.normal_encounters.len() != 6 || set.back_attacks.len() != 2 {
+        return Err("Encounter table needs 6 normal encounters and 2 back attacks".to_string());
+    }
+    let normal_total: u32 = set.normal_encounters.iter().map(|pair| pair.rate as u32).sum();
+    if set.active && normal_total != NORMAL_RATE_TOTAL {
+        return Err(format!("Normal encounter rates add up to {}, expected {}", normal_total, NORMAL_RATE_TOTAL));
+    }
+
+    let mut bytes = vec![set.active as u8, set.encounter_rate];
+    let pairs = set
+        .normal_encounters
+        .iter()
+        .chain(set.back_attacks.iter())
+        .chain([&set.side_attack, &set.pincer_attack]);
+    for pair in pairs {
+        bytes.extend_from_slice(&encode_encounter_pair(pair)?.to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+fn encounter_section_address(addresses: &FF7Addresses) -> Result<u32, String> {
+    let field_data_ptr = read_memory_int(addresses.field_data_ptr)?;
+    if field_data_ptr == 0 {
+        return Err("No field is loaded".to_string());
+    }
+    let section7_offset = read_memory_int(addresses.field_section_offsets + 6 * 4)?;
+    Ok(field_data_ptr + 4 + section7_offset)
+}
+
+fn encounter_table_address(addresses: &FF7Addresses, table_index: u32) -> Result<u32, String> {
+    if table_index > 1 {
+        return Err("Invalid encounter table index".to_string());
+    }
+    Ok(encounter_section_address(addresses)? + table_index * ENCOUNTER_SET_SIZE as u32)
+}
+
+/// Writes both tables into the loaded field's section 7. Both are validated before anything is written.
+pub fn write_field_encounters(tables: &FieldEncounterTables, addresses: &FF7Addresses) -> Result<(), String> {
+    let table1 = encode_encounter_set(&tables.table1)?;
+    let table2 = encode_encounter_set(&tables.table2)?;
+    write_memory_buffer(encounter_table_address(addresses, 0)?, table1)?;
+    write_memory_buffer(encounter_table_address(addresses, 1)?, table2)?;
+    Ok(())
+}
+
+pub fn write_field_encounter_active(table_index: u32, active: bool, addresses: &FF7Addresses) -> Result<(), String> {
+    write_memory_byte(encounter_table_address(addresses, table_index)?, active as u8)
+}
+
+pub fn write_field_encounter_rate(table_index: u32, encounter_rate: u8, addresses: &FF7Addresses) -> Result<(), String> {
+    write_memory_byte(encounter_table_address(addresses, table_index)? + 1, encounter_rate)
+}
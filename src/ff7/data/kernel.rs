@@ -1,7 +1,7 @@
 use crate::ff7::addresses::FF7Addresses;
 use crate::ff7::types::kernel::ItemData;
+use crate::ff7::data::kernel_bin::parse_item_data;
 use crate::utils::memory::*;
-use crate::utils::flip_bits;
 
 pub fn read_name(address: u32, max_length: u32) -> Result<String, String> {
     let mut name = Vec::new();
@@ -90,26 +90,6 @@ pub fn read_attack_names(addresses: &FF7Addresses) -> Result<Vec<String>, String
 }
 
 pub fn read_item_data(addresses: &FF7Addresses) -> Result<Vec<ItemData>, String> {
-    let mut items: Vec<ItemData> = Vec::new();
-    let addr = addresses.kernel_item_data;
-    for i in 0..128 {
-        let item_addr = addr + i * 28;
-        let item_data = ItemData {
-            camera_move_id: read_memory_short(item_addr + 0x08)? as u16,
-            restriction_mask: read_memory_short(item_addr + 0x0a)? as u16,
-            target_flags: read_memory_byte(item_addr + 0x0c)? as u8,
-            attack_effect_id: read_memory_byte(item_addr + 0x0d)? as u8,
-            damage_func: read_memory_byte(item_addr + 0x0e)? as u8,
-            power: read_memory_byte(item_addr + 0x0f)? as u8,
-            condition: read_memory_byte(item_addr + 0x10)? as u8,
-            status_effect_change: flip_bits(read_memory_byte(item_addr + 0x11)? as u8),
-            attack_additional_effect: flip_bits(read_memory_byte(item_addr + 0x12)? as u8),
-            additional_effect_modifier: flip_bits(read_memory_byte(item_addr + 0x13)? as u8),
-            status_effects: flip_bits(read_memory_int(item_addr + 0x14)? as u32),
-            attack_element: read_memory_short(item_addr + 0x18)? as u16,
-            special_attack_flags: flip_bits(read_memory_short(item_addr + 0x1a)? as u16),
-        };
-        items.push(item_data);
-    }
-    Ok(items)
+    let buffer = read_memory_buffer(addresses.kernel_item_data, 128 * 28)?;
+    buffer.chunks_exact(28).map(parse_item_data).collect()
 }
@@ -0,0 +1,902 @@
+use crate::ff7::ff7text::{decode_text, encode_text};
+use crate::ff7::types::kernel::*;
+use crate::utils::flip_bits;
+use crate::utils::lzs::{lzs_compress, lzs_decompress};
+use crate::utils::process;
+use byteorder::{LittleEndian, ReadBytesExt};
+use flate2::read::GzDecoder;
+use flate2::{Compression, GzBuilder};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+const KERNEL_SECTION_COUNT: usize = 27;
+const KERNEL_SECTION_HEADER_SIZE: usize = 6;
+const KERNEL_TEXT_SECTION_START: usize = 9;
+const KERNEL_TEXT_SECTION_COUNT: usize = 18;
+
+const COMMAND_DATA_SIZE: usize = 8;
+const ATTACK_DATA_SIZE: usize = 28;
+const ITEM_DATA_SIZE: usize = 28;
+pub const WEAPON_DATA_SIZE: usize = 44;
+pub const ARMOR_DATA_SIZE: usize = 36;
+pub const ACCESSORY_DATA_SIZE: usize = 16;
+pub const MATERIA_DATA_SIZE: usize = 20;
+
+// Battle and growth data layout (kernel section 3)
+const GROWTH_CHARACTER_COUNT: usize = 11;
+const GROWTH_CHARACTER_RECORD_SIZE: usize = 0x38;
+const GROWTH_STAT_BONUS_OFFSET: usize = 0x268;
+const GROWTH_HP_BONUS_OFFSET: usize = 0x274;
+const GROWTH_MP_BONUS_OFFSET: usize = 0x280;
+const GROWTH_STAT_CURVES_OFFSET: usize = 0x28C;
+const GROWTH_STAT_CURVE_COUNT: usize = 64;
+const GROWTH_CHARACTER_AI_OFFSET: usize = 0x68C;
+const GROWTH_RANDOM_TABLE_OFFSET: usize = 0xE8C;
+const GROWTH_SCENE_LOOKUP_OFFSET: usize = 0xF8C;
+const GROWTH_SPELL_ORDER_OFFSET: usize = 0xFCC;
+const GROWTH_DATA_SIZE: usize = 0x1004;
+
+/// Reads and parses kernel.bin from the game directory.
+pub fn read_kernel_bin() -> Result<KernelBin, String> {
+    let game_dir = process::get_current_dir()
+        .ok_or_else(|| "Failed to get current game directory".to_string())?;
+    let kernel_bin_path = Path::new(&game_dir).join("data/lang-en/kernel/kernel.bin");
+
+    read_kernel_bin_from_path(&kernel_bin_path)
+}
+
+pub fn read_kernel_bin_from_path(kernel_bin_path: &Path) -> Result<KernelBin, String> {
+    let data = fs::read(kernel_bin_path)
+        .map_err(|e| format!("Failed to read kernel.bin: {}", e))?;
+    parse_kernel_bin(&data)
+}
+
+pub fn parse_kernel_bin(data: &[u8]) -> Result<KernelBin, String> {
+    let sections = split_kernel_sections(data)?;
+    if sections.len() != KERNEL_SECTION_COUNT {
+        return Err(format!(
+            "Expected {} kernel sections, found {}",
+            KERNEL_SECTION_COUNT,
+            sections.len()
+        ));
+    }
+
+    let text_sections: Vec<&[u8]> = sections[KERNEL_TEXT_SECTION_START..]
+        .iter()
+        .map(|section| section.data.as_slice())
+        .collect();
+    let texts = parse_kernel_texts(&text_sections)?;
+
+    Ok(KernelBin {
+        commands: split_records(&sections[0].data, COMMAND_DATA_SIZE)
+            .map(parse_command_data)
+            .collect::<Result<_, _>>()?,
+        attacks: split_records(&sections[1].data, ATTACK_DATA_SIZE)
+            .map(parse_attack_data)
+            .collect::<Result<_, _>>()?,
+        battle_growth: parse_battle_growth_data(&sections[2].data)?,
+        initial_savemap: sections[3].data.clone(),
+        items: split_records(&sections[4].data, ITEM_DATA_SIZE)
+            .map(parse_item_data)
+            .collect::<Result<_, _>>()?,
+        weapons: split_records(&sections[5].data, WEAPON_DATA_SIZE)
+            .map(parse_weapon_data)
+            .collect::<Result<_, _>>()?,
+        armor: split_records(&sections[6].data, ARMOR_DATA_SIZE)
+            .map(parse_armor_data)
+            .collect::<Result<_, _>>()?,
+        accessories: split_records(&sections[7].data, ACCESSORY_DATA_SIZE)
+            .map(parse_accessory_data)
+            .collect::<Result<_, _>>()?,
+        materia: split_records(&sections[8].data, MATERIA_DATA_SIZE)
+            .map(parse_materia_data)
+            .collect::<Result<_, _>>()?,
+        texts,
+        sections,
+    })
+}
+
+/// Splits kernel.bin into its gzip-compressed sections and decompresses each of them.
+/// Every section is prefixed with a 6-byte header: compressed size, decompressed size and file type.
+pub fn split_kernel_sections(data: &[u8]) -> Result<Vec<KernelSection>, String> {
+    let mut sections = Vec::new();
+    let mut pos = 0;
+
+    while pos + KERNEL_SECTION_HEADER_SIZE <= data.len() {
+        let mut header = Cursor::new(&data[pos..pos + KERNEL_SECTION_HEADER_SIZE]);
+        let compressed_size = header.read_u16::<LittleEndian>().map_err(|e| e.to_string())? as usize;
+        let decompressed_size = header.read_u16::<LittleEndian>().map_err(|e| e.to_string())? as usize;
+        let file_type = header.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
+        pos += KERNEL_SECTION_HEADER_SIZE;
+
+        if pos + compressed_size > data.len() {
+            return Err(format!("Kernel section {} exceeds file bounds", sections.len()));
+        }
+
+        let mut decoder = GzDecoder::new(&data[pos..pos + compressed_size]);
+        let mut decompressed = Vec::with_capacity(decompressed_size);
+        decoder
+            .read_to_end(&mut decompressed)
+            .map_err(|e| format!("Failed to decompress kernel section {}: {}", sections.len(), e))?;
+
+        if decompressed.len() != decompressed_size {
+            log::warn!(target: "backend", "Kernel section {} decompressed to {} bytes, header says {}", sections.len(), decompressed.len(), decompressed_size);
+        }
+
+        sections.push(KernelSection {
+            file_type,
+            data: decompressed,
+            compressed: data[pos..pos + compressed_size].to_vec(),
+        });
+        pos += compressed_size;
+    }
+
+    Ok(sections)
+}
+
+fn split_records(data: &[u8], record_size: usize) -> impl Iterator<Item = &[u8]> {
+    data.chunks_exact(record_size)
+}
+
+pub fn parse_command_data(bytes: &[u8]) -> Result<CommandData, String> {
+    let mut cursor = Cursor::new(bytes);
+    let initial_cursor_action = cursor.read_u8().map_err(|e| e.to_string())?;
+    let target_flags = TargetFlags::from_bits_retain(cursor.read_u8().map_err(|e| e.to_string())?);
+    let _unknown = cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
+    Ok(CommandData {
+        initial_cursor_action,
+        target_flags,
+        camera_movement_single: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+        camera_movement_multiple: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+    })
+}
+
+/// Parses a 28-byte attack record. The same layout is used by kernel magic and scene.bin enemy attacks.
+pub fn parse_attack_data(bytes: &[u8]) -> Result<AttackData, String> {
+    let mut cursor = Cursor::new(bytes);
+    let attack_percent = cursor.read_u8().map_err(|e| e.to_string())?;
+    let impact_effect_id = cursor.read_u8().map_err(|e| e.to_string())?;
+    let target_hurt_action_idx = cursor.read_u8().map_err(|e| e.to_string())?;
+    let _unknown_03 = cursor.read_u8().map_err(|e| e.to_string())?;
+    Ok(AttackData {
+        attack_percent,
+        impact_effect_id,
+        target_hurt_action_idx,
+        mp_cost: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+        impact_sound: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+        camera_movement_single: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+        camera_movement_multiple: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+        target_flags: TargetFlags::from_bits_retain(cursor.read_u8().map_err(|e| e.to_string())?),
+        attack_effect_id: cursor.read_u8().map_err(|e| e.to_string())?,
+        damage_func: cursor.read_u8().map_err(|e| e.to_string())?,
+        power: cursor.read_u8().map_err(|e| e.to_string())?,
+        condition: cursor.read_u8().map_err(|e| e.to_string())?,
+        status_effect_change: cursor.read_u8().map_err(|e| e.to_string())?,
+        attack_additional_effect: cursor.read_u8().map_err(|e| e.to_string())?,
+        additional_effect_modifier: cursor.read_u8().map_err(|e| e.to_string())?,
+        status_effects: StatusFlags::from_bits_retain(cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?),
+        attack_element: ElementFlags::from_bits_retain(cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?),
+        special_attack_flags: flip_bits(cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?),
+    })
+}
+
+/// Parses a 28-byte item record, matching the values `read_item_data` returns from live memory.
+pub fn parse_item_data(bytes: &[u8]) -> Result<ItemData, String> {
+    let mut cursor = Cursor::new(bytes);
+    let mut _unknown = [0u8; 8];
+    cursor.read_exact(&mut _unknown).map_err(|e| e.to_string())?;
+    Ok(ItemData {
+        camera_move_id: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+        restriction_mask: cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?,
+        target_flags: TargetFlags::from_bits_retain(cursor.read_u8().map_err(|e| e.to_string())?),
+        attack_effect_id: cursor.read_u8().map_err(|e| e.to_string())?,
+        damage_func: cursor.read_u8().map_err(|e| e.to_string())?,
+        power: cursor.read_u8().map_err(|e| e.to_string())?,
+        condition: cursor.read_u8().map_err(|e| e.to_string())?,
+        status_effect_change: flip_bits(cursor.read_u8().map_err(|e| e.to_string())?),
+        attack_additional_effect: flip_bits(cursor.read_u8().map_err(|e| e.to_string())?),
+        additional_effect_modifier: flip_bits(cursor.read_u8().map_err(|e| e.to_string())?),
+        status_effects: StatusFlags::from_bits_retain(flip_bits(cursor.read_u32::<LittleEndian>().map_err(|e| e.to_string())?)),
+        attack_element: ElementFlags::from_bits_retain(cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?),
+        special_attack_flags: flip_bits(cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())?),
+    })
+}
+
+fn parse_stat_boosts(types: &[u8], values: &[u8]) -> Vec<StatBoost> {
+    types
+        .iter()
+        .zip(values.iter())
+        .filter(|(&stat, _)| stat != 0xFF)
+        .map(|(&stat, &value)| StatBoost { stat, value })
+        .collect()
+}
+
+fn encode_stat_boosts(boosts: &[StatBoost], types: &mut [u8], values: &mut [u8]) {
+    for i in 0..types.len() {
+        match boosts.get(i) {
+            Some(boost) => {
+                types[i] = boost.stat;
+                values[i] = boost.value;
+            }
+            None => types[i] = 0xFF,
+        }
+    }
+}
+
+fn parse_materia_slots(bytes: &[u8]) -> [MateriaSlotLayout; 8] {
+    std::array::from_fn(|i| MateriaSlotLayout::from_byte(bytes[i]))
+}
+
+fn encode_materia_slots(slots: &[MateriaSlotLayout; 8], bytes: &mut [u8]) {
+    for (i, slot) in slots.iter().enumerate() {
+        bytes[i] = slot.to_byte();
+    }
+}
+
+pub fn parse_weapon_data(bytes: &[u8]) -> Result<WeaponData, String> {
+    if bytes.len() < WEAPON_DATA_SIZE {
+        return Err(format!("Weapon record must be {} bytes, got {}", WEAPON_DATA_SIZE, bytes.len()));
+    }
+    let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+    Ok(WeaponData {
+        target_flags: TargetFlags::from_bits_retain(bytes[0x00]),
+        damage_func: bytes[0x02],
+        attack: bytes[0x04],
+        status_attack: bytes[0x05],
+        materia_growth_rate: bytes[0x06],
+        critical_rate: bytes[0x07],
+        hit_rate: bytes[0x08],
+        model_id: bytes[0x09],
+        camera_movement: u16_at(0x0C),
+        equip_mask: u16_at(0x0E),
+        attack_element: ElementFlags::from_bits_retain(u16_at(0x10)),
+        stat_boosts: parse_stat_boosts(&bytes[0x14..0x18], &bytes[0x18..0x1C]),
+        materia_slots: parse_materia_slots(&bytes[0x1C..0x24]),
+        hit_sound: bytes[0x24],
+        critical_sound: bytes[0x25],
+        miss_sound: bytes[0x26],
+        impact_effect_id: bytes[0x27],
+        special_attack_flags: flip_bits(u16_at(0x28)),
+        restriction_mask: u16_at(0x2A),
+    })
+}
+
+pub fn encode_weapon_data(weapon: &WeaponData, bytes: &mut [u8]) {
+    bytes[0x00] = weapon.target_flags.bits();
+    bytes[0x02] = weapon.damage_func;
+    bytes[0x04] = weapon.attack;
+    bytes[0x05] = weapon.status_attack;
+    bytes[0x06] = weapon.materia_growth_rate;
+    bytes[0x07] = weapon.critical_rate;
+    bytes[0x08] = weapon.hit_rate;
+    bytes[0x09] = weapon.model_id;
+    bytes[0x0C..0x0E].copy_from_slice(&weapon.camera_movement.to_le_bytes());
+    bytes[0x0E..0x10].copy_from_slice(&weapon.equip_mask.to_le_bytes());
+    bytes[0x10..0x12].copy_from_slice(&weapon.attack_element.bits().to_le_bytes());
+    let (types, values) = bytes[0x14..0x1C].split_at_mut(4);
+    encode_stat_boosts(&weapon.stat_boosts, types, values);
+    encode_materia_slots(&weapon.materia_slots, &mut bytes[0x1C..0x24]);
+    bytes[0x24] = weapon.hit_sound;
+    bytes[0x25] = weapon.critical_sound;
+    bytes[0x26] = weapon.miss_sound;
+    bytes[0x27] = weapon.impact_effect_id;
+    bytes[0x28..0x2A].copy_from_slice(&flip_bits(weapon.special_attack_flags).to_le_bytes());
+    bytes[0x2A..0x2C].copy_from_slice(&weapon.restriction_mask.to_le_bytes());
+}
+
+pub fn parse_armor_data(bytes: &[u8]) -> Result<ArmorData, String> {
+    if bytes.len() < ARMOR_DATA_SIZE {
+        return Err(format!("Armor record must be {} bytes, got {}", ARMOR_DATA_SIZE, bytes.len()));
+    }
+    let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+    Ok(ArmorData {
+        element_defense_mode: bytes[0x01],
+        defense: bytes[0x02],
+        magic_defense: bytes[0x03],
+        defense_rate: bytes[0x04],
+        magic_defense_rate: bytes[0x05],
+        status_defense: bytes[0x06],
+        materia_slots: parse_materia_slots(&bytes[0x09..0x11]),
+        materia_growth_rate: bytes[0x11],
+        equip_mask: u16_at(0x12),
+        element_defense: ElementFlags::from_bits_retain(u16_at(0x14)),
+        stat_boosts: parse_stat_boosts(&bytes[0x18..0x1C], &bytes[0x1C..0x20]),
+        restriction_mask: u16_at(0x20),
+    })
+}
+
+pub fn encode_armor_data(armor: &ArmorData, bytes: &mut [u8]) {
+    bytes[0x01] = armor.element_defense_mode;
+    bytes[0x02] = armor.defense;
+    bytes[0x03] = armor.magic_defense;
+    bytes[0x04] = armor.defense_rate;
+    bytes[0x05] = armor.magic_defense_rate;
+    bytes[0x06] = armor.status_defense;
+    encode_materia_slots(&armor.materia_slots, &mut bytes[0x09..0x11]);
+    bytes[0x11] = armor.materia_growth_rate;
+    bytes[0x12..0x14].copy_from_slice(&armor.equip_mask.to_le_bytes());
+    bytes[0x14..0x16].copy_from_slice(&armor.element_defense.bits().to_le_bytes());
+    let (types, values) = bytes[0x18..0x20].split_at_mut(4);
+    encode_stat_boosts(&armor.stat_boosts, types, values);
+    bytes[0x20..0x22].copy_from_slice(&armor.restriction_mask.to_le_bytes());
+}
+
+pub fn parse_accessory_data(bytes: &[u8]) -> Result<AccessoryData, String> {
+    if bytes.len() < ACCESSORY_DATA_SIZE {
+        return Err(format!("Accessory record must be {} bytes, got {}", ACCESSORY_DATA_SIZE, bytes.len()));
+    }
+    let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+    Ok(AccessoryData {
+        stat_boosts: parse_stat_boosts(&bytes[0x00..0x02], &bytes[0x02..0x04]),
+        element_defense_mode: bytes[0x04],
+        special_effect: bytes[0x05],
+        element_defense: ElementFlags::from_bits_retain(u16_at(0x06)),
+        status_defense: StatusFlags::from_bits_retain(u32::from_le_bytes([bytes[0x08], bytes[0x09], bytes[0x0A], bytes[0x0B]])),
+        equip_mask: u16_at(0x0C),
+        restriction_mask: u16_at(0x0E),
+    })
+}
+
+pub fn encode_accessory_data(accessory: &AccessoryData, bytes: &mut [u8]) {
+    let (types, values) = bytes[0x00..0x04].split_at_mut(2);
+    encode_stat_boosts(&accessory.stat_boosts, types, values);
+    bytes[0x04] = accessory.element_defense_mode;
+    bytes[0x05] = accessory.special_effect;
+    bytes[0x06..0x08].copy_from_slice(&accessory.element_defense.bits().to_le_bytes());
+    bytes[0x08..0x0C].copy_from_slice(&accessory.status_defense.bits().to_le_bytes());
+    bytes[0x0C..0x0E].copy_from_slice(&accessory.equip_mask.to_le_bytes());
+    bytes[0x0E..0x10].copy_from_slice(&accessory.restriction_mask.to_le_bytes());
+}
+
+pub fn parse_materia_data(bytes: &[u8]) -> Result<MateriaData, String> {
+    if bytes.len() < MATERIA_DATA_SIZE {
+        return Err(format!("Materia record must be {} bytes, got {}", MATERIA_DATA_SIZE, bytes.len()));
+    }
+    let type_byte = bytes[0x0D];
+    Ok(MateriaData {
+        ap_thresholds: std::array::from_fn(|i| u16::from_le_bytes([bytes[i * 2], bytes[i * 2 + 1]])),
+        equip_effect: bytes[0x08],
+        status_effects: StatusFlags::from_bits_retain(u32::from_le_bytes([bytes[0x09], bytes[0x0A], bytes[0x0B], 0])),
+        element: bytes[0x0C],
+        type_byte,
+        materia_type: MateriaType::from_type_byte(type_byte),
+        subtype: type_byte >> 4,
+        abilities: std::array::from_fn(|i| bytes[0x0E + i]),
+    })
+}
+
+/// Writes a materia record over `bytes`. The type is taken from `type_byte`.
+pub fn encode_materia_data(materia: &MateriaData, bytes: &mut [u8]) {
+    for (i, threshold) in materia.ap_thresholds.iter().enumerate() {
+        bytes[i * 2..i * 2 + 2].copy_from_slice(&threshold.to_le_bytes());
+    }
+    bytes[0x08] = materia.equip_effect;
+    bytes[0x09..0x0C].copy_from_slice(&materia.status_effects.bits().to_le_bytes()[..3]);
+    bytes[0x0C] = materia.element;
+    bytes[0x0D] = materia.type_byte;
+    bytes[0x0E..0x14].copy_from_slice(&materia.abilities);
+}
+
+fn parse_character_growth_record(bytes: &[u8]) -> Result<CharacterGrowthRecord, String> {
+    let mut cursor = Cursor::new(bytes);
+    let mut curves = [0u8; 9];
+    cursor.read_exact(&mut curves).map_err(|e| e.to_string())?;
+    let mut _unknown = [0u8; 3];
+    cursor.read_exact(&mut _unknown).map_err(|e| e.to_string())?;
+    let mut limit_commands = [0u8; 12];
+    cursor.read_exact(&mut limit_commands).map_err(|e| e.to_string())?;
+    let mut limit_kills_required = [0u16; 2];
+    cursor.read_u16_into::<LittleEndian>(&mut limit_kills_required).map_err(|e| e.to_string())?;
+    let mut limit_uses_required = [0u16; 6];
+    cursor.read_u16_into::<LittleEndian>(&mut limit_uses_required).map_err(|e| e.to_string())?;
+    let mut limit_hp_divisors = [0u32; 4];
+    cursor.read_u32_into::<LittleEndian>(&mut limit_hp_divisors).map_err(|e| e.to_string())?;
+
+    Ok(CharacterGrowthRecord {
+        strength_curve: curves[0],
+        vitality_curve: curves[1],
+        magic_curve: curves[2],
+        spirit_curve: curves[3],
+        dexterity_curve: curves[4],
+        luck_curve: curves[5],
+        hp_curve: curves[6],
+        mp_curve: curves[7],
+        exp_curve: curves[8],
+        limit_commands,
+        limit_kills_required,
+        limit_uses_required,
+        limit_hp_divisors,
+    })
+}
+
+fn parse_stat_curve(bytes: &[u8]) -> StatCurve {
+    // Each curve is 8 (gradient, base) pairs, one for every level bracket
+    StatCurve {
+        gradients: std::array::from_fn(|i| bytes[i * 2]),
+        bases: std::array::from_fn(|i| bytes[i * 2 + 1]),
+    }
+}
+
+pub fn parse_battle_growth_data(data: &[u8]) -> Result<BattleGrowthData, String> {
+    if data.len() < GROWTH_DATA_SIZE {
+        return Err(format!(
+            "Battle and growth data is too short: {} bytes, expected {}",
+            data.len(),
+            GROWTH_DATA_SIZE
+        ));
+    }
+
+    let characters = data[..GROWTH_STAT_BONUS_OFFSET]
+        .chunks_exact(GROWTH_CHARACTER_RECORD_SIZE)
+        .take(GROWTH_CHARACTER_COUNT)
+        .map(parse_character_growth_record)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let table = |offset: usize| -> [u8; 12] { std::array::from_fn(|i| data[offset + i]) };
+
+    let stat_curves = data[GROWTH_STAT_CURVES_OFFSET..GROWTH_CHARACTER_AI_OFFSET]
+        .chunks_exact(16)
+        .take(GROWTH_STAT_CURVE_COUNT)
+        .map(parse_stat_curve)
+        .collect();
+
+    Ok(BattleGrowthData {
+        characters,
+        stat_random_bonus: table(GROWTH_STAT_BONUS_OFFSET),
+        hp_random_bonus: table(GROWTH_HP_BONUS_OFFSET),
+        mp_random_bonus: table(GROWTH_MP_BONUS_OFFSET),
+        stat_curves,
+        character_ai: data[GROWTH_CHARACTER_AI_OFFSET..GROWTH_RANDOM_TABLE_OFFSET].to_vec(),
+        random_table: data[GROWTH_RANDOM_TABLE_OFFSET..GROWTH_SCENE_LOOKUP_OFFSET].to_vec(),
+        scene_lookup_table: data[GROWTH_SCENE_LOOKUP_OFFSET..GROWTH_SPELL_ORDER_OFFSET].to_vec(),
+        spell_order: data[GROWTH_SPELL_ORDER_OFFSET..].to_vec(),
+    })
+}
+
+/// Expands the dictionary references (0xF9) used by kernel text strings.
+/// The argument byte holds the length in its two high bits and the backwards distance in the low six.
+fn expand_kernel_text(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        i += 1;
+        if c == 0xFF {
+            break;
+        } else if c == 0xF9 && i < bytes.len() {
+            let arg = bytes[i] as usize;
+            i += 1;
+            let length = ((arg >> 6) & 0x3) * 2 + 4;
+            let distance = (arg & 0x3F) + 1;
+            if distance <= out.len() {
+                let start = out.len() - distance;
+                for j in 0..length {
+                    out.push(out[start + j]);
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Splits a kernel text section, a table of u16 offsets followed by 0xFF-terminated strings,
+/// into the bytes of every string with its dictionary references expanded.
+fn split_kernel_text_section(data: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    if data.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    let mut cursor = Cursor::new(data);
+    let first_offset = cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())? as usize;
+    let count = first_offset / 2;
+    cursor.set_position(0);
+
+    let mut strings = Vec::with_capacity(count);
+    for _ in 0..count {
+        let offset = cursor.read_u16::<LittleEndian>().map_err(|e| e.to_string())? as usize;
+        if offset >= data.len() {
+            strings.push(Vec::new());
+        } else {
+            strings.push(expand_kernel_text(&data[offset..]));
+        }
+    }
+
+    Ok(strings)
+}
+
+/// Strings that can't be decoded read as "???"; the writer keeps their original bytes
+/// as long as they aren't edited.
+fn decode_kernel_string(bytes: &[u8]) -> String {
+    decode_text(bytes).unwrap_or_else(|_| String::from("???"))
+}
+
+/// Parses a kernel text section: a table of u16 offsets followed by 0xFF-terminated strings.
+pub fn parse_kernel_text_section(data: &[u8]) -> Result<Vec<String>, String> {
+    Ok(split_kernel_text_section(data)?.iter().map(|bytes| decode_kernel_string(bytes)).collect())
+}
+
+pub fn parse_kernel_texts(sections: &[&[u8]]) -> Result<KernelTexts, String> {
+    if sections.len() != KERNEL_TEXT_SECTION_COUNT {
+        return Err(format!(
+            "Expected {} kernel text sections, found {}",
+            KERNEL_TEXT_SECTION_COUNT,
+            sections.len()
+        ));
+    }
+
+    Ok(KernelTexts {
+        command_descriptions: parse_kernel_text_section(sections[0])?,
+        magic_descriptions: parse_kernel_text_section(sections[1])?,
+        item_descriptions: parse_kernel_text_section(sections[2])?,
+        weapon_descriptions: parse_kernel_text_section(sections[3])?,
+        armor_descriptions: parse_kernel_text_section(sections[4])?,
+        accessory_descriptions: parse_kernel_text_section(sections[5])?,
+        materia_descriptions: parse_kernel_text_section(sections[6])?,
+        key_item_descriptions: parse_kernel_text_section(sections[7])?,
+        command_names: parse_kernel_text_section(sections[8])?,
+        magic_names: parse_kernel_text_section(sections[9])?,
+        item_names: parse_kernel_text_section(sections[10])?,
+        weapon_names: parse_kernel_text_section(sections[11])?,
+        armor_names: parse_kernel_text_section(sections[12])?,
+        accessory_names: parse_kernel_text_section(sections[13])?,
+        materia_names: parse_kernel_text_section(sections[14])?,
+        key_item_names: parse_kernel_text_section(sections[15])?,
+        battle_texts: parse_kernel_text_section(sections[16])?,
+        summon_attack_names: parse_kernel_text_section(sections[17])?,
+    })
+}
+
+fn kernel_text_lists(texts: &KernelTexts) -> [&Vec<String>; KERNEL_TEXT_SECTION_COUNT] {
+    [
+        &texts.command_descriptions,
+        &texts.magic_descriptions,
+        &texts.item_descriptions,
+        &texts.weapon_descriptions,
+        &texts.armor_descriptions,
+        &texts.accessory_descriptions,
+        &texts.materia_descriptions,
+        &texts.key_item_descriptions,
+        &texts.command_names,
+        &texts.magic_names,
+        &texts.item_names,
+        &texts.weapon_names,
+        &texts.armor_names,
+        &texts.accessory_names,
+        &texts.materia_names,
+        &texts.key_item_names,
+        &texts.battle_texts,
+        &texts.summon_attack_names,
+    ]
+}
+
+// --- Writers ---
+
+pub fn encode_command_data(command: &CommandData, bytes: &mut [u8]) {
+    bytes[0x00] = command.initial_cursor_action;
+    bytes[0x01] = command.target_flags.bits();
+    bytes[0x04..0x06].copy_from_slice(&command.camera_movement_single.to_le_bytes());
+    bytes[0x06..0x08].copy_from_slice(&command.camera_movement_multiple.to_le_bytes());
+}
+
+/// Writes an attack record over `bytes`, leaving the unknown byte at 0x03 untouched.
+pub fn encode_attack_data(attack: &AttackData, bytes: &mut [u8]) {
+    bytes[0x00] = attack.attack_percent;
+    bytes[0x01] = attack.impact_effect_id;
+    bytes[0x02] = attack.target_hurt_action_idx;
+    bytes[0x04..0x06].copy_from_slice(&attack.mp_cost.to_le_bytes());
+    bytes[0x06..0x08].copy_from_slice(&attack.impact_sound.to_le_bytes());
+    bytes[0x08..0x0A].copy_from_slice(&attack.camera_movement_single.to_le_bytes());
+    bytes[0x0A..0x0C].copy_from_slice(&attack.camera_movement_multiple.to_le_bytes());
+    bytes[0x0C] = attack.target_flags.bits();
+    bytes[0x0D] = attack.attack_effect_id;
+    bytes[0x0E] = attack.damage_func;
+    bytes[0x0F] = attack.power;
+    bytes[0x10] = attack.condition;
+    bytes[0x11] = attack.status_effect_change;
+    bytes[0x12] = attack.attack_additional_effect;
+    bytes[0x13] = attack.additional_effect_modifier;
+    bytes[0x14..0x18].copy_from_slice(&attack.status_effects.bits().to_le_bytes());
+    bytes[0x18..0x1A].copy_from_slice(&attack.attack_element.bits().to_le_bytes());
+    bytes[0x1A..0x1C].copy_from_slice(&flip_bits(attack.special_attack_flags).to_le_bytes());
+}
+
+/// Writes an item record over `bytes`, undoing the bit flips applied by `parse_item_data`.
+pub fn encode_item_data(item: &ItemData, bytes: &mut [u8]) {
+    bytes[0x08..0x0A].copy_from_slice(&item.camera_move_id.to_le_bytes());
+    bytes[0x0A..0x0C].copy_from_slice(&item.restriction_mask.to_le_bytes());
+    bytes[0x0C] = item.target_flags.bits();
+    bytes[0x0D] = item.attack_effect_id;
+    bytes[0x0E] = item.damage_func;
+    bytes[0x0F] = item.power;
+    bytes[0x10] = item.condition;
+    bytes[0x11] = flip_bits(item.status_effect_change);
+    bytes[0x12] = flip_bits(item.attack_additional_effect);
+    bytes[0x13] = flip_bits(item.additional_effect_modifier);
+    bytes[0x14..0x18].copy_from_slice(&flip_bits(item.status_effects.bits()).to_le_bytes());
+    bytes[0x18..0x1A].copy_from_slice(&item.attack_element.bits().to_le_bytes());
+    bytes[0x1A..0x1C].copy_from_slice(&flip_bits(item.special_attack_flags).to_le_bytes());
+}
+
+fn encode_character_growth_record(record: &CharacterGrowthRecord, bytes: &mut [u8]) {
+    let curves = [
+        record.strength_curve,
+        record.vitality_curve,
+        record.magic_curve,
+        record.spirit_curve,
+        record.dexterity_curve,
+        record.luck_curve,
+        record.hp_curve,
+        record.mp_curve,
+        record.exp_curve,
+    ];
+    bytes[0x00..0x09].copy_from_slice(&curves);
+    bytes[0x0C..0x18].copy_from_slice(&record.limit_commands);
+    for (i, kills) in record.limit_kills_required.iter().enumerate() {
+        bytes[0x18 + i * 2..0x1A + i * 2].copy_from_slice(&kills.to_le_bytes());
+    }
+    for (i, uses) in record.limit_uses_required.iter().enumerate() {
+        bytes[0x1C + i * 2..0x1E + i * 2].copy_from_slice(&uses.to_le_bytes());
+    }
+    for (i, divisor) in record.limit_hp_divisors.iter().enumerate() {
+        bytes[0x28 + i * 4..0x2C + i * 4].copy_from_slice(&divisor.to_le_bytes());
+    }
+}
+
+pub fn encode_battle_growth_data(growth: &BattleGrowthData, bytes: &mut Vec<u8>) -> Result<(), String> {
+    let tail_size = growth.spell_order.len();
+    bytes.resize(GROWTH_SPELL_ORDER_OFFSET + tail_size, 0);
+
+    for (record, chunk) in growth
+        .characters
+        .iter()
+        .zip(bytes[..GROWTH_STAT_BONUS_OFFSET].chunks_exact_mut(GROWTH_CHARACTER_RECORD_SIZE))
+    {
+        encode_character_growth_record(record, chunk);
+    }
+    bytes[GROWTH_STAT_BONUS_OFFSET..GROWTH_HP_BONUS_OFFSET].copy_from_slice(&growth.stat_random_bonus);
+    bytes[GROWTH_HP_BONUS_OFFSET..GROWTH_MP_BONUS_OFFSET].copy_from_slice(&growth.hp_random_bonus);
+    bytes[GROWTH_MP_BONUS_OFFSET..GROWTH_STAT_CURVES_OFFSET].copy_from_slice(&growth.mp_random_bonus);
+    for (curve, chunk) in growth
+        .stat_curves
+        .iter()
+        .zip(bytes[GROWTH_STAT_CURVES_OFFSET..GROWTH_CHARACTER_AI_OFFSET].chunks_exact_mut(16))
+    {
+        for i in 0..8 {
+            chunk[i * 2] = curve.gradients[i];
+            chunk[i * 2 + 1] = curve.bases[i];
+        }
+    }
+
+    let blocks = [
+        (GROWTH_CHARACTER_AI_OFFSET, GROWTH_RANDOM_TABLE_OFFSET, &growth.character_ai, "character AI"),
+        (GROWTH_RANDOM_TABLE_OFFSET, GROWTH_SCENE_LOOKUP_OFFSET, &growth.random_table, "random table"),
+        (GROWTH_SCENE_LOOKUP_OFFSET, GROWTH_SPELL_ORDER_OFFSET, &growth.scene_lookup_table, "scene lookup table"),
+    ];
+    for (start, end, block, name) in blocks {
+        if block.len() != end - start {
+            return Err(format!("Growth data {} must be {} bytes, got {}", name, end - start, block.len()));
+        }
+        bytes[start..end].copy_from_slice(block);
+    }
+    bytes[GROWTH_SPELL_ORDER_OFFSET..].copy_from_slice(&growth.spell_order);
+
+    Ok(())
+}
+
+/// Lays out already encoded strings behind their offset table, each terminated with 0xFF
+fn build_kernel_text_section(strings: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    let table_size = strings.len() * 2;
+    let mut table = Vec::with_capacity(table_size);
+    let mut body = Vec::new();
+    for string in strings {
+        let offset = table_size + body.len();
+        if offset > u16::MAX as usize {
+            return Err("Kernel text section exceeds 64 KiB".to_string());
+        }
+        table.extend_from_slice(&(offset as u16).to_le_bytes());
+        body.extend_from_slice(string);
+        body.push(0xFF);
+    }
+    table.extend(body);
+    Ok(table)
+}
+
+/// Strings are written out in full: 0xF9 dictionary references are expanded on read but
+/// never generated, so a re-encoded section can be larger than the original.
+pub fn encode_kernel_text_section(texts: &[String]) -> Result<Vec<u8>, String> {
+    let strings = texts.iter().map(|text| encode_text(text)).collect::<Result<Vec<_>, _>>()?;
+    build_kernel_text_section(&strings)
+}
+
+/// Rebuilds the 18 text sections. Sections whose strings are unchanged keep their original bytes;
+/// in the others only the edited strings are re-encoded, so battle text control codes and strings
+/// that don't decode survive edits elsewhere in the section.
+fn rebuild_kernel_text_sections(texts: &KernelTexts, originals: &[&[u8]]) -> Result<Vec<Vec<u8>>, String> {
+    kernel_text_lists(texts)
+        .iter()
+        .enumerate()
+        .map(|(i, strings)| {
+            let original = originals.get(i).copied().unwrap_or(&[]);
+            if !original.is_empty() && parse_kernel_text_section(original)? == **strings {
+                return Ok(original.to_vec());
+            }
+
+            let original_strings = split_kernel_text_section(original)?;
+            let encoded = strings
+                .iter()
+                .enumerate()
+                .map(|(j, text)| match original_strings.get(j) {
+                    Some(bytes) if decode_kernel_string(bytes) == *text => Ok(bytes.clone()),
+                    _ => encode_text(text),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            build_kernel_text_section(&encoded)
+        })
+        .collect()
+}
+
+fn encode_records<T>(records: &[T], original: &[u8], record_size: usize, encode: impl Fn(&T, &mut [u8])) -> Vec<u8> {
+    let mut data = original.to_vec();
+    data.resize(records.len() * record_size, 0xFF);
+    for (record, chunk) in records.iter().zip(data.chunks_exact_mut(record_size)) {
+        encode(record, chunk);
+    }
+    data
+}
+
+/// Re-encodes the typed kernel data on top of the original section bytes.
+pub fn build_kernel_sections(kernel: &KernelBin) -> Result<Vec<Vec<u8>>, String> {
+    if kernel.sections.len() != KERNEL_SECTION_COUNT {
+        return Err(format!(
+            "Expected {} kernel sections, found {}",
+            KERNEL_SECTION_COUNT,
+            kernel.sections.len()
+        ));
+    }
+    let original = |i: usize| kernel.sections[i].data.as_slice();
+
+    let mut growth = original(2).to_vec();
+    encode_battle_growth_data(&kernel.battle_growth, &mut growth)?;
+
+    let mut sections = vec![
+        encode_records(&kernel.commands, original(0), COMMAND_DATA_SIZE, encode_command_data),
+        encode_records(&kernel.attacks, original(1), ATTACK_DATA_SIZE, encode_attack_data),
+        growth,
+        kernel.initial_savemap.clone(),
+        encode_records(&kernel.items, original(4), ITEM_DATA_SIZE, encode_item_data),
+        encode_records(&kernel.weapons, original(5), WEAPON_DATA_SIZE, encode_weapon_data),
+        encode_records(&kernel.armor, original(6), ARMOR_DATA_SIZE, encode_armor_data),
+        encode_records(&kernel.accessories, original(7), ACCESSORY_DATA_SIZE, encode_accessory_data),
+        encode_records(&kernel.materia, original(8), MATERIA_DATA_SIZE, encode_materia_data),
+    ];
+
+    let original_texts: Vec<&[u8]> = (KERNEL_TEXT_SECTION_START..KERNEL_SECTION_COUNT).map(original).collect();
+    sections.extend(rebuild_kernel_text_sections(&kernel.texts, &original_texts)?);
+
+    Ok(sections)
+}
+
+/// Gzip-compresses a section, copying the header fields (mtime, flags, OS) of the original stream when available.
+fn gzip_section(data: &[u8], original: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzBuilder::new().write(Vec::new(), Compression::best());
+    encoder.write_all(data).map_err(|e| e.to_string())?;
+    let mut compressed = encoder.finish().map_err(|e| e.to_string())?;
+    if original.len() >= 10 && compressed.len() >= 10 {
+        compressed[4..10].copy_from_slice(&original[4..10]);
+    }
+    Ok(compressed)
+}
+
+pub fn pack_kernel_bin(kernel: &KernelBin) -> Result<Vec<u8>, String> {
+    let sections = build_kernel_sections(kernel)?;
+    let mut out = Vec::new();
+
+    for (section, data) in kernel.sections.iter().zip(sections.iter()) {
+        let compressed = if *data == section.data && !section.compressed.is_empty() {
+            section.compressed.clone()
+        } else {
+            gzip_section(data, &section.compressed)?
+        };
+        if compressed.len() > u16::MAX as usize || data.len() > u16::MAX as usize {
+            return Err("Kernel section exceeds 64 KiB".to_string());
+        }
+
+        out.extend_from_slice(&(compressed.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        out.extend_from_slice(&section.file_type.to_le_bytes());
+        out.extend(compressed);
+    }
+
+    Ok(out)
+}
+
+pub fn write_kernel_bin(kernel: &KernelBin, kernel_bin_path: &Path) -> Result<(), String> {
+    let data = pack_kernel_bin(kernel)?;
+    fs::write(kernel_bin_path, data).map_err(|e| format!("Failed to write kernel.bin: {}", e))
+}
+
+// --- kernel2.bin ---
+
+/// Reads and parses kernel2.bin from the game directory.
+pub fn read_kernel2_bin() -> Result<Kernel2Bin, String> {
+    let game_dir = process::get_current_dir()
+        .ok_or_else(|| "Failed to get current game directory".to_string())?;
+    let kernel2_bin_path = Path::new(&game_dir).join("data/lang-en/kernel/kernel2.bin");
+
+    read_kernel2_bin_from_path(&kernel2_bin_path)
+}
+
+pub fn read_kernel2_bin_from_path(kernel2_bin_path: &Path) -> Result<Kernel2Bin, String> {
+    let data = fs::read(kernel2_bin_path)
+        .map_err(|e| format!("Failed to read kernel2.bin: {}", e))?;
+    parse_kernel2_bin(&data)
+}
+
+/// kernel2.bin is an LZS-compressed list of the 18 text sections, each prefixed with its u32 size.
+pub fn parse_kernel2_bin(data: &[u8]) -> Result<Kernel2Bin, String> {
+    let decompressed = lzs_decompress(data)?;
+    let mut cursor = Cursor::new(decompressed.as_slice());
+    let mut sections = Vec::with_capacity(KERNEL_TEXT_SECTION_COUNT);
+
+    for i in 0..KERNEL_TEXT_SECTION_COUNT {
+        let size = cursor
+            .read_u32::<LittleEndian>()
+            .map_err(|e| format!("Failed to read kernel2 section {} size: {}", i, e))? as usize;
+        let mut section = vec![0u8; size];
+        cursor
+            .read_exact(&mut section)
+            .map_err(|e| format!("Failed to read kernel2 section {}: {}", i, e))?;
+        sections.push(section);
+    }
+
+    let section_refs: Vec<&[u8]> = sections.iter().map(|s| s.as_slice()).collect();
+    Ok(Kernel2Bin {
+        texts: parse_kernel_texts(&section_refs)?,
+        sections,
+        original: data.to_vec(),
+    })
+}
+
+pub fn pack_kernel2_bin(kernel2: &Kernel2Bin) -> Result<Vec<u8>, String> {
+    let originals: Vec<&[u8]> = kernel2.sections.iter().map(|s| s.as_slice()).collect();
+    let sections = rebuild_kernel_text_sections(&kernel2.texts, &originals)?;
+    if !kernel2.original.is_empty() && sections == kernel2.sections {
+        return Ok(kernel2.original.clone());
+    }
+
+    let mut raw = Vec::new();
+    for section in &sections {
+        raw.extend_from_slice(&(section.len() as u32).to_le_bytes());
+        raw.extend_from_slice(section);
+    }
+    Ok(lzs_compress(&raw))
+}
+
+pub fn write_kernel2_bin(kernel2: &Kernel2Bin, kernel2_bin_path: &Path) -> Result<(), String> {
+    let data = pack_kernel2_bin(kernel2)?;
+    fs::write(kernel2_bin_path, data).map_err(|e| format!("Failed to write kernel2.bin: {}", e))
+}
+
+/// Builds a kernel2.bin carrying the same texts as the given kernel.bin.
+pub fn kernel2_from_kernel(kernel: &KernelBin) -> Result<Kernel2Bin, String> {
+    if kernel.sections.len() != KERNEL_SECTION_COUNT {
+        return Err(format!(
+            "Expected {} kernel sections, found {}",
+            KERNEL_SECTION_COUNT,
+            kernel.sections.len()
+        ));
+    }
+    let originals: Vec<&[u8]> = kernel.sections[KERNEL_TEXT_SECTION_START..]
+        .iter()
+        .map(|section| section.data.as_slice())
+        .collect();
+    let sections = rebuild_kernel_text_sections(&kernel.texts, &originals)?;
+    Ok(Kernel2Bin {
+        texts: kernel.texts.clone(),
+        sections,
+        original: Vec::new(),
+    })
+}
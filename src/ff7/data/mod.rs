@@ -2,6 +2,7 @@ pub mod general;
 pub mod battle;
 pub mod field;
 pub mod kernel;
+pub mod kernel_bin;
 pub mod world;
 pub mod party;
 pub mod chocobos;
@@ -10,6 +11,7 @@ pub use general::*;
 pub use battle::*;
 pub use field::*;
 pub use kernel::*;
+pub use kernel_bin::*;
 pub use world::*;
 pub use party::*;
 pub use chocobos::*;
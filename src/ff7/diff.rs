@@ -0,0 +1,129 @@
+use crate::ff7::types::battle::{Scene, SceneChange, SceneChangeKind};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Numeric arrays longer than this (AI bytecode) are compared and reported as a whole
+const MAX_ITEMIZED_ARRAY: usize = 16;
+
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => format!("\"{}\"", text),
+        Value::Array(values) if is_leaf_array(values) => format!("<{} bytes>", values.len()),
+        _ => value.to_string(),
+    }
+}
+
+fn is_leaf_array(values: &[Value]) -> bool {
+    values.len() > MAX_ITEMIZED_ARRAY && values.iter().all(Value::is_number)
+}
+
+fn push_change(changes: &mut Vec<SceneChange>, scene_id: u16, path: String, change: SceneChangeKind) {
+    changes.push(SceneChange { scene_id, path, change });
+}
+
+fn diff_values(scene_id: u16, path: &str, old: &Value, new: &Value, changes: &mut Vec<SceneChange>) {
+    let child = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields)) => {
+            for (key, old_value) in old_fields {
+                match new_fields.get(key) {
+                    Some(new_value) => diff_values(scene_id, &child(key), old_value, new_value, changes),
+                    None => push_change(changes, scene_id, child(key), SceneChangeKind::Removed { value: render_value(old_value) }),
+                }
+            }
+            for (key, new_value) in new_fields.iter().filter(|(key, _)| !old_fields.contains_key(*key)) {
+                push_change(changes, scene_id, child(key), SceneChangeKind::Added { value: render_value(new_value) });
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) if !is_leaf_array(old_items) && !is_leaf_array(new_items) => {
+            for (i, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                diff_values(scene_id, &format!("{}[{}]", path, i), old_item, new_item, changes);
+            }
+            for (i, old_item) in old_items.iter().enumerate().skip(new_items.len()) {
+                push_change(changes, scene_id, format!("{}[{}]", path, i), SceneChangeKind::Removed { value: render_value(old_item) });
+            }
+            for (i, new_item) in new_items.iter().enumerate().skip(old_items.len()) {
+                push_change(changes, scene_id, format!("{}[{}]", path, i), SceneChangeKind::Added { value: render_value(new_item) });
+            }
+        }
+        _ if old != new => push_change(
+            changes,
+            scene_id,
+            path.to_string(),
+            SceneChangeKind::Changed { old: render_value(old), new: render_value(new) },
+        ),
+        _ => {}
+    }
+}
+
+/// Attacks are keyed by attack id so an insertion doesn't show up as a change to every later slot
+fn attacks_by_id(scene: &Value) -> Value {
+    let attacks = scene["attacks"].as_array().cloned().unwrap_or_default();
+    Value::Object(
+        attacks
+            .into_iter()
+            .map(|attack| (format!("[0x{:04X}]", attack["id"].as_u64().unwrap_or(0)), attack))
+            .collect(),
+    )
+}
+
+fn diff_scene(scene_id: u16, old: &Scene, new: &Scene, changes: &mut Vec<SceneChange>) -> Result<(), String> {
+    let mut old = serde_json::to_value(old).map_err(|e| e.to_string())?;
+    let mut new = serde_json::to_value(new).map_err(|e| e.to_string())?;
+    let old_attacks = attacks_by_id(&old);
+    let new_attacks = attacks_by_id(&new);
+    for scene in [&mut old, &mut new] {
+        if let Some(fields) = scene.as_object_mut() {
+            fields.remove("attacks");
+        }
+    }
+
+    diff_values(scene_id, "", &old, &new, changes);
+    let mut attack_changes = Vec::new();
+    diff_values(scene_id, "", &old_attacks, &new_attacks, &mut attack_changes);
+    changes.extend(attack_changes.into_iter().map(|mut change| {
+        change.path = format!("attacks{}", change.path);
+        change
+    }));
+    Ok(())
+}
+
+/// Field-level changes from `old` to `new`, scene by scene. Scenes are paired by `Scene::id`;
+/// a scene found in only one list is reported as added or removed as a whole.
+pub fn diff_scenes(old: &[Scene], new: &[Scene]) -> Result<Vec<SceneChange>, String> {
+    let old_scenes: BTreeMap<u16, &Scene> = old.iter().map(|scene| (scene.id, scene)).collect();
+    let new_scenes: BTreeMap<u16, &Scene> = new.iter().map(|scene| (scene.id, scene)).collect();
+    let scene_ids: BTreeSet<u16> = old_scenes.keys().chain(new_scenes.keys()).copied().collect();
+
+    let mut changes = Vec::new();
+    for scene_id in scene_ids {
+        match (old_scenes.get(&scene_id), new_scenes.get(&scene_id)) {
+            (Some(old_scene), Some(new_scene)) => diff_scene(scene_id, old_scene, new_scene, &mut changes)?,
+            (Some(_), None) => push_change(&mut changes, scene_id, String::new(), SceneChangeKind::Removed { value: "scene".to_string() }),
+            (None, Some(_)) => push_change(&mut changes, scene_id, String::new(), SceneChangeKind::Added { value: "scene".to_string() }),
+            (None, None) => {}
+        }
+    }
+    Ok(changes)
+}
+
+/// Renders changes grouped by scene, one line per change
+pub fn render_scene_diff(changes: &[SceneChange]) -> String {
+    let mut text = String::new();
+    let mut current_scene = None;
+    for change in changes {
+        if current_scene != Some(change.scene_id) {
+            let _ = writeln!(text, "Scene {}:", change.scene_id);
+            current_scene = Some(change.scene_id);
+        }
+        let path = if change.path.is_empty() { "scene" } else { change.path.as_str() };
+        let _ = match &change.change {
+            SceneChangeKind::Changed { old, new } => writeln!(text, "  ~ {}: {} -> {}", path, old, new),
+            SceneChangeKind::Added { value } => writeln!(text, "  + {}: {}", path, value),
+            SceneChangeKind::Removed { value } => writeln!(text, "  - {}: {}", path, value),
+        };
+    }
+    text
+}
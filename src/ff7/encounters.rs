@@ -0,0 +1,155 @@
+use crate::ff7::addresses::FF7Addresses;
+use crate::ff7::data::field::read_field_encounters;
+use crate::ff7::data::kernel::read_item_names;
+use crate::ff7::loot::{drop_chances, steal_chances};
+use crate::ff7::rng::{advance_step, encounter_triggers};
+use crate::ff7::types::battle::SceneDatabase;
+use crate::ff7::types::field::{
+    EncounterChance, EncounterEnemyReport, EncounterKind, EncounterPair, EncounterPrediction, EncounterReport,
+    EncounterSlotReport, EncounterTableReport, FieldEncounterSet, FieldEncounterTables,
+};
+use crate::ff7::types::general::{EncounterRng, RandomTable};
+
+/// Encounter slot rates are out of 64
+const RATE_DIVISOR: f32 = 64.0;
+
+/// Danger added per walking step is 4096 / encounter rate, running doubles it
+const DANGER_SCALE: u32 = 4096;
+
+/// Once the danger value passes 0xFF00 every roll triggers, so no search runs longer than this
+const MAX_PREDICTED_STEPS: u32 = 0x10000;
+
+/// The second table is used while the field's alternative encounters are enabled
+pub fn active_encounter_set(tables: &FieldEncounterTables, alt_encounters_enabled: bool) -> &FieldEncounterSet {
+    if alt_encounters_enabled { &tables.table2 } else { &tables.table1 }
+}
+
+pub fn danger_increment(encounter_rate: u8, running: bool) -> u32 {
+    if encounter_rate == 0 {
+        return 0;
+    }
+    let increment = DANGER_SCALE / encounter_rate as u32;
+    if running { increment * 2 } else { increment }
+}
+
+/// Number of steps until the next encounter triggers, counting the triggering step.
+/// `step_fraction` is how far the player has moved into the current step. It changes how much
+/// movement is left before the next step, not the number of steps, so it isn't used here.
+pub fn steps_until_encounter(rng: &EncounterRng, table: &RandomTable, encounter_rate: u8, running: bool) -> Option<u32> {
+    let increment = danger_increment(encounter_rate, running);
+    if increment == 0 {
+        return None;
+    }
+    let mut rng = *rng;
+    (1..=MAX_PREDICTED_STEPS).find(|_| {
+        advance_step(&mut rng, increment);
+        encounter_triggers(&rng, table)
+    })
+}
+
+/// Chance of each formation once an encounter triggers. Back attacks, the side attack and the
+/// pincer attack are each rolled in that order against their rate; if none hit, a normal
+/// formation is picked by its rate bucket.
+pub fn formation_chances(set: &FieldEncounterSet) -> Vec<EncounterChance> {
+    let specials = set
+        .back_attacks
+        .iter()
+        .enumerate()
+        .map(|(slot, pair)| (EncounterKind::BackAttack, slot, pair))
+        .chain([(EncounterKind::SideAttack, 0, &set.side_attack), (EncounterKind::PincerAttack, 0, &set.pincer_attack)]);
+
+    let chance = |kind: EncounterKind, slot: usize, pair: &EncounterPair, chance: f32| EncounterChance {
+        kind,
+        slot: slot as u8,
+        encounter_id: pair.encounter_id,
+        chance,
+    };
+
+    let mut remaining = 1.0;
+    let mut chances = Vec::new();
+    for (kind, slot, pair) in specials {
+        if pair.rate == 0 {
+            continue;
+        }
+        let roll = (pair.rate as f32 / RATE_DIVISOR).min(1.0);
+        chances.push(chance(kind, slot, pair, remaining * roll));
+        remaining *= 1.0 - roll;
+    }
+    for (slot, pair) in set.normal_encounters.iter().enumerate() {
+        if pair.rate > 0 {
+            chances.push(chance(EncounterKind::Normal, slot, pair, remaining * pair.rate as f32 / RATE_DIVISOR));
+        }
+    }
+    chances
+}
+
+pub fn predict_encounter(rng: &EncounterRng, table: &RandomTable, set: &FieldEncounterSet) -> EncounterPrediction {
+    if !set.active {
+        return EncounterPrediction { walking_steps: None, running_steps: None, formations: Vec::new() };
+    }
+    EncounterPrediction {
+        walking_steps: steps_until_encounter(rng, table, set.encounter_rate, false),
+        running_steps: steps_until_encounter(rng, table, set.encounter_rate, true),
+        formations: formation_chances(set),
+    }
+}
+
+fn encounter_table_report(
+    set: &FieldEncounterSet,
+    database: &SceneDatabase,
+    attacker_level: u8,
+    item_names: &[String],
+) -> EncounterTableReport {
+    let slots = formation_chances(set)
+        .into_iter()
+        .map(|chance| {
+            let enemies = database
+                .formation_details(chance.encounter_id)
+                .map(|details| {
+                    details
+                        .enemies
+                        .iter()
+                        .filter_map(|formation_enemy| formation_enemy.enemy.as_ref())
+                        .map(|enemy| EncounterEnemyReport {
+                            enemy_id: enemy.id,
+                            name: enemy.name.clone(),
+                            level: enemy.level,
+                            hp: enemy.hp,
+                            steal: steal_chances(attacker_level, enemy, false, item_names),
+                            drop: drop_chances(enemy, item_names),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            EncounterSlotReport {
+                kind: chance.kind,
+                slot: chance.slot,
+                battle_id: chance.encounter_id,
+                percentage: chance.chance * 100.0,
+                enemies,
+            }
+        })
+        .collect();
+    EncounterTableReport { active: set.active, encounter_rate: set.encounter_rate, slots }
+}
+
+/// Joins both encounter tables with their scene.bin formations. Steal chances are for an
+/// attacker of `attacker_level`; pass empty `item_names` to leave item names unresolved.
+pub fn encounter_report(
+    tables: &FieldEncounterTables,
+    database: &SceneDatabase,
+    attacker_level: u8,
+    item_names: &[String],
+) -> EncounterReport {
+    EncounterReport {
+        table1: encounter_table_report(&tables.table1, database, attacker_level, item_names),
+        table2: encounter_table_report(&tables.table2, database, attacker_level, item_names),
+    }
+}
+
+/// Builds the report for the field currently loaded in the game
+pub fn read_encounter_report(addresses: &FF7Addresses, database: &SceneDatabase, attacker_level: u8) -> Result<EncounterReport, String> {
+    let tables = read_field_encounters(addresses)?;
+    let item_names = read_item_names(addresses)?;
+    Ok(encounter_report(&tables, database, attacker_level, &item_names))
+}
@@ -126,3 +126,69 @@ pub fn decode_text(buf: &[u8]) -> Result<String, String> {
 
     Ok(text)
 }
+
+pub fn encode_text(text: &str) -> Result<Vec<u8>, String> {
+    let chars = Chars::new();
+    let mut buf = Vec::new();
+    let mut iter = text.chars().peekable();
+
+    while let Some(c) = iter.next() {
+        if c == '\\' {
+            let escaped = iter.next().ok_or_else(|| "Spurious escape at end of string".to_string())?;
+            buf.push(encode_normal_char(&chars, escaped)?);
+        } else if c == '{' {
+            let mut token = String::from("{");
+            loop {
+                let t = iter.next().ok_or_else(|| format!("Unterminated token {}", token))?;
+                token.push(t);
+                if t == '}' {
+                    break;
+                }
+            }
+
+            let args: Vec<&str> = token[1..token.len() - 1].split(' ').collect();
+            if args[0] == "WAIT" && args.len() == 2 {
+                let arg: u16 = args[1].parse().map_err(|_| format!("Invalid WAIT argument in {}", token))?;
+                buf.extend_from_slice(&[0xFE, 0xDD]);
+                buf.extend_from_slice(&arg.to_le_bytes());
+            } else if args[0] == "STR" && args.len() == 3 {
+                let offset: u16 = args[1].parse().map_err(|_| format!("Invalid STR offset in {}", token))?;
+                let length: u16 = args[2].parse().map_err(|_| format!("Invalid STR length in {}", token))?;
+                buf.extend_from_slice(&[0xFE, 0xE2]);
+                buf.extend_from_slice(&offset.to_le_bytes());
+                buf.extend_from_slice(&length.to_le_bytes());
+            } else if let Some((&k, _)) = chars.field_control.iter().find(|(_, &v)| v == token) {
+                buf.extend_from_slice(&[0xFE, k]);
+            } else if let Some((&k, _)) = chars.field_special.iter().find(|(_, &v)| v == token) {
+                buf.push(k);
+                // Decoding appends a line break after {NEW}
+                if k == 0xE8 && iter.peek() == Some(&'\n') {
+                    iter.next();
+                }
+            } else {
+                return Err(format!("Unknown token {}", token));
+            }
+        } else if let Ok(k) = encode_normal_char(&chars, c) {
+            buf.push(k);
+        } else {
+            let (&k, _) = chars
+                .field_special
+                .iter()
+                .find(|(_, &v)| v.chars().count() == 1 && v.starts_with(c))
+                .ok_or_else(|| format!("Character {:?} cannot be encoded", c))?;
+            buf.push(k);
+        }
+    }
+
+    Ok(buf)
+}
+
+fn encode_normal_char(chars: &Chars, c: char) -> Result<u8, String> {
+    chars
+        .normal
+        .chars()
+        .take(0xE0)
+        .position(|t| t == c)
+        .map(|i| i as u8)
+        .ok_or_else(|| format!("Character {:?} cannot be encoded", c))
+}
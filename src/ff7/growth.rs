@@ -0,0 +1,198 @@
+use crate::ff7::types::kernel::{BattleGrowthData, StatCurve};
+use crate::ff7::types::party::{LevelPrediction, PartyMember, StatRange};
+
+pub const MAX_LEVEL: u8 = 99;
+const MAX_PRIMARY_STAT: i32 = 255;
+const MAX_HP: i32 = 9999;
+const MAX_MP: i32 = 999;
+
+/// Random roll range the game adds to every stat difference on level up
+const RANDOM_ROLLS: std::ops::RangeInclusive<i32> = 1..=8;
+
+/// Maps a level to its curve bracket: 2-11, 12-21, 22-31, 32-41, 42-51, 52-61, 62-81, 82-99
+pub fn level_bracket(level: u8) -> usize {
+    match level {
+        0..=11 => 0,
+        12..=21 => 1,
+        22..=31 => 2,
+        32..=41 => 3,
+        42..=51 => 4,
+        52..=61 => 5,
+        62..=81 => 6,
+        _ => 7,
+    }
+}
+
+fn curve_at(curve: &StatCurve, level: u8) -> (i32, i32) {
+    let bracket = level_bracket(level);
+    (curve.gradients[bracket] as i32, curve.bases[bracket] as i32)
+}
+
+fn bonus(table: &[u8; 12], difference: i32) -> i32 {
+    table[difference.clamp(0, 11) as usize] as i32
+}
+
+pub fn primary_stat_baseline(curve: &StatCurve, level: u8) -> i32 {
+    let (gradient, base) = curve_at(curve, level);
+    base + gradient * level as i32 / 100
+}
+
+pub fn hp_baseline(curve: &StatCurve, level: u8) -> i32 {
+    let (gradient, base) = curve_at(curve, level);
+    base * 40 + (level as i32 - 1) * gradient
+}
+
+pub fn mp_baseline(curve: &StatCurve, level: u8) -> i32 {
+    let (gradient, base) = curve_at(curve, level);
+    base * 2 + (level as i32 - 1) * gradient / 10
+}
+
+/// Primary stat points gained when reaching `level`, for a given random roll (1-8)
+pub fn primary_stat_gain(growth: &BattleGrowthData, curve: &StatCurve, level: u8, current: i32, roll: i32) -> i32 {
+    let difference = roll + primary_stat_baseline(curve, level) - current;
+    bonus(&growth.stat_random_bonus, difference)
+}
+
+/// Base HP gained when reaching `level`, for a given random roll (1-8)
+pub fn hp_gain(growth: &BattleGrowthData, curve: &StatCurve, level: u8, current: i32, roll: i32) -> i32 {
+    let (gradient, _) = curve_at(curve, level);
+    let difference = roll + 100 * hp_baseline(curve, level) / current.max(1) - 100;
+    bonus(&growth.hp_random_bonus, difference) * gradient / 100
+}
+
+/// Base MP gained when reaching `level`, for a given random roll (1-8)
+pub fn mp_gain(growth: &BattleGrowthData, curve: &StatCurve, level: u8, current: i32, roll: i32) -> i32 {
+    let (gradient, _) = curve_at(curve, level);
+    let difference = roll + 100 * mp_baseline(curve, level) / current.max(1) - 100;
+    bonus(&growth.mp_random_bonus, difference) * gradient / 1000
+}
+
+fn curve(growth: &BattleGrowthData, index: u8) -> Result<&StatCurve, String> {
+    growth
+        .stat_curves
+        .get(index as usize)
+        .ok_or_else(|| format!("Stat curve {} does not exist", index))
+}
+
+/// Total EXP needed to reach every level (index = level), following the character's EXP curve.
+/// Each level costs gradient * level^2 / 10 EXP on top of the previous one.
+pub fn exp_table(growth: &BattleGrowthData, character_id: u8) -> Result<Vec<u32>, String> {
+    let record = growth
+        .characters
+        .get(character_id as usize)
+        .ok_or_else(|| format!("No growth data for character {}", character_id))?;
+    let exp_curve = curve(growth, record.exp_curve)?;
+
+    let mut table = vec![0u32; MAX_LEVEL as usize + 1];
+    for level in 2..=MAX_LEVEL {
+        let (gradient, _) = curve_at(exp_curve, level);
+        let level_cost = gradient as u32 * level as u32 * level as u32 / 10;
+        table[level as usize] = table[level as usize - 1] + level_cost;
+    }
+    Ok(table)
+}
+
+/// EXP the member still needs for the next level according to the kernel curves,
+/// to be compared with the `exp_to_next_level` value the game keeps in the savemap.
+pub fn predicted_exp_to_next_level(member: &PartyMember, growth: &BattleGrowthData) -> Result<u32, String> {
+    if member.level >= MAX_LEVEL {
+        return Ok(0);
+    }
+    let table = exp_table(growth, member.id)?;
+    Ok(table[member.level as usize + 1].saturating_sub(member.exp))
+}
+
+struct GrowthPath {
+    primary: [i32; 6],
+    hp: i32,
+    mp: i32,
+}
+
+impl GrowthPath {
+    fn new(member: &PartyMember) -> Self {
+        GrowthPath {
+            primary: [
+                member.strength as i32,
+                member.vitality as i32,
+                member.magic as i32,
+                member.spirit as i32,
+                member.dexterity as i32,
+                member.luck as i32,
+            ],
+            hp: member.base_hp as i32,
+            mp: member.base_mp as i32,
+        }
+    }
+
+    fn level_up(&mut self, growth: &BattleGrowthData, curves: &[&StatCurve; 8], level: u8, roll: i32) {
+        for (i, stat) in self.primary.iter_mut().enumerate() {
+            *stat = (*stat + primary_stat_gain(growth, curves[i], level, *stat, roll)).min(MAX_PRIMARY_STAT);
+        }
+        self.hp = (self.hp + hp_gain(growth, curves[6], level, self.hp, roll)).min(MAX_HP);
+        self.mp = (self.mp + mp_gain(growth, curves[7], level, self.mp, roll)).min(MAX_MP);
+    }
+}
+
+fn average_gain(gain: impl Fn(i32) -> i32) -> f32 {
+    let total: i32 = RANDOM_ROLLS.map(gain).sum();
+    total as f32 / RANDOM_ROLLS.count() as f32
+}
+
+/// Predicts base stats for every level from the member's next level up to `target_level`.
+/// The range bounds follow the lowest and highest random roll on every level up.
+pub fn predict_level_ups(
+    member: &PartyMember,
+    growth: &BattleGrowthData,
+    target_level: u8,
+) -> Result<Vec<LevelPrediction>, String> {
+    let record = growth
+        .characters
+        .get(member.id as usize)
+        .ok_or_else(|| format!("No growth data for character {}", member.id))?;
+    let curves = [
+        curve(growth, record.strength_curve)?,
+        curve(growth, record.vitality_curve)?,
+        curve(growth, record.magic_curve)?,
+        curve(growth, record.spirit_curve)?,
+        curve(growth, record.dexterity_curve)?,
+        curve(growth, record.luck_curve)?,
+        curve(growth, record.hp_curve)?,
+        curve(growth, record.mp_curve)?,
+    ];
+    let exp = exp_table(growth, member.id)?;
+
+    let mut low = GrowthPath::new(member);
+    let mut high = GrowthPath::new(member);
+    let mut expected = GrowthPath::new(member);
+    let mut predictions = Vec::new();
+
+    for level in member.level.saturating_add(1)..=target_level.min(MAX_LEVEL) {
+        let expected_hp_gain = average_gain(|roll| hp_gain(growth, curves[6], level, expected.hp, roll));
+        let expected_mp_gain = average_gain(|roll| mp_gain(growth, curves[7], level, expected.mp, roll));
+
+        low.level_up(growth, &curves, level, *RANDOM_ROLLS.start());
+        high.level_up(growth, &curves, level, *RANDOM_ROLLS.end());
+        expected.level_up(growth, &curves, level, (RANDOM_ROLLS.start() + RANDOM_ROLLS.end()) / 2);
+
+        let range = |a: i32, b: i32| StatRange {
+            min: a.min(b) as u16,
+            max: a.max(b) as u16,
+        };
+        predictions.push(LevelPrediction {
+            level,
+            exp_required: exp[level as usize],
+            strength: range(low.primary[0], high.primary[0]),
+            vitality: range(low.primary[1], high.primary[1]),
+            magic: range(low.primary[2], high.primary[2]),
+            spirit: range(low.primary[3], high.primary[3]),
+            dexterity: range(low.primary[4], high.primary[4]),
+            luck: range(low.primary[5], high.primary[5]),
+            hp: range(low.hp, high.hp),
+            mp: range(low.mp, high.mp),
+            expected_hp_gain,
+            expected_mp_gain,
+        });
+    }
+
+    Ok(predictions)
+}
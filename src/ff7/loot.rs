@@ -0,0 +1,124 @@
+use crate::ff7::damage::damage_rolls;
+use crate::ff7::types::battle::{DamageActor, DamageAttack, DamageContext, FormationDetails, SceneEnemy, SceneEnemyItem};
+use crate::ff7::types::items::{EnemyLoot, ItemChance};
+use crate::ff7::types::kernel::ElementFlags;
+
+/// Item rates are out of 64
+const RATE_DIVISOR: f32 = 64.0;
+
+/// Morph hits like a normal attack at 1/8 of its power
+const MORPH_ATTACK: DamageAttack = DamageAttack { damage_func: 0x11, power: 2, attack_element: ElementFlags::empty() };
+
+fn slot_rate(item: &SceneEnemyItem) -> f32 {
+    ((item.rate() as f32 + 1.0) / RATE_DIVISOR).min(1.0)
+}
+
+fn item_name(item_names: &[String], item_id: u16) -> Option<String> {
+    item_names.get(item_id as usize).filter(|name| !name.is_empty()).cloned()
+}
+
+/// Slots are rolled in order and the first success wins, so each chance is reduced by
+/// the chance of an earlier slot succeeding.
+fn sequential_chances(
+    enemy: &SceneEnemy,
+    steal: bool,
+    multiplier: f32,
+    item_names: &[String],
+) -> Vec<ItemChance> {
+    let mut remaining = 1.0;
+    let mut chances = Vec::new();
+    for (slot, item) in enemy.items.iter().enumerate() {
+        if item.item_id == 0xFFFF || item.is_steal() != steal {
+            continue;
+        }
+        let roll = (slot_rate(item) * multiplier).clamp(0.0, 1.0);
+        chances.push(ItemChance {
+            slot: slot as u8,
+            item_id: item.item_id,
+            name: item_name(item_names, item.item_id),
+            chance: remaining * roll,
+        });
+        remaining *= 1.0 - roll;
+    }
+    chances
+}
+
+/// Level-based steal multiplier: [(40 + attacker level - enemy level) * 512 / 100] out of 256.
+/// Steals done through Sneak Attack at the start of battle skip the level check.
+pub fn steal_multiplier(attacker_level: u8, enemy_level: u8, sneak_attack: bool) -> f32 {
+    if sneak_attack {
+        return 1.0;
+    }
+    let base = (40 + attacker_level as i32 - enemy_level as i32).max(0);
+    (base * 512 / 100) as f32 / 256.0
+}
+
+pub fn steal_chances(attacker_level: u8, enemy: &SceneEnemy, sneak_attack: bool, item_names: &[String]) -> Vec<ItemChance> {
+    let multiplier = steal_multiplier(attacker_level, enemy.level, sneak_attack);
+    sequential_chances(enemy, true, multiplier, item_names)
+}
+
+/// At most one item drops after the battle
+pub fn drop_chances(enemy: &SceneEnemy, item_names: &[String]) -> Vec<ItemChance> {
+    sequential_chances(enemy, false, 1.0, item_names)
+}
+
+/// Morph only turns the enemy into an item when the hit is the killing blow
+pub fn morph_chance(attacker: &DamageActor, enemy: &SceneEnemy, current_hp: u32, context: &DamageContext) -> Result<f32, String> {
+    if enemy.morph_item_id == 0xFFFF {
+        return Ok(0.0);
+    }
+    let mut target = DamageActor::from(enemy);
+    target.hp = current_hp;
+    let (rolls, instant_death) = damage_rolls(attacker, &target, &MORPH_ATTACK, context)?;
+    if instant_death {
+        return Ok(1.0);
+    }
+    let kills = rolls.iter().filter(|damage| **damage >= current_hp as i32).count();
+    Ok(kills as f32 / rolls.len() as f32)
+}
+
+/// `current_hp` is the HP the morph chance is computed against
+pub fn enemy_loot(
+    attacker: &DamageActor,
+    enemy: &SceneEnemy,
+    current_hp: u32,
+    sneak_attack: bool,
+    item_names: &[String],
+) -> Result<EnemyLoot, String> {
+    let morph = if enemy.morph_item_id == 0xFFFF {
+        None
+    } else {
+        Some(ItemChance {
+            slot: 0,
+            item_id: enemy.morph_item_id,
+            name: item_name(item_names, enemy.morph_item_id),
+            chance: morph_chance(attacker, enemy, current_hp, &DamageContext::default())?,
+        })
+    };
+    Ok(EnemyLoot {
+        enemy_id: enemy.id,
+        steal: steal_chances(attacker.level, enemy, sneak_attack, item_names),
+        drop: drop_chances(enemy, item_names),
+        morph,
+    })
+}
+
+/// Loot for every enemy in a formation. Morph chances use the live HP when the enemy's
+/// actor has been read, and full HP otherwise.
+pub fn formation_loot(
+    attacker: &DamageActor,
+    formation: &FormationDetails,
+    sneak_attack: bool,
+    item_names: &[String],
+) -> Result<Vec<EnemyLoot>, String> {
+    let mut loot = Vec::new();
+    for formation_enemy in &formation.enemies {
+        let Some(enemy) = &formation_enemy.enemy else {
+            continue;
+        };
+        let current_hp = formation_enemy.actor.as_ref().map_or(enemy.hp, |actor| actor.hp);
+        loot.push(enemy_loot(attacker, enemy, current_hp, sneak_attack, item_names)?);
+    }
+    Ok(loot)
+}
@@ -1,6 +1,16 @@
 pub mod addresses;
+pub mod ai;
+pub mod bestiary;
+pub mod damage;
+pub mod encounters;
 pub mod data;
+pub mod diff;
 pub mod ff7text;
+pub mod growth;
+pub mod loot;
+pub mod query;
+pub mod rng;
+pub mod timeline;
 pub mod types;
 
 use addresses::FF7Addresses;
@@ -22,14 +32,15 @@ pub struct FF7Data {
     pub party_members: Vec<PartyMember>,
 }
 
-pub fn read_data() -> Result<FF7Data, String> {
+/// `kernel` is only used for the battle allies' equipment, see `read_battle_allies`
+pub fn read_data(kernel: &KernelBin) -> Result<FF7Data, String> {
     let addresses = FF7Addresses::new();
     Ok(FF7Data {
         basic: read_basic_data(&addresses)?,
         field_models: read_field_models(&addresses)?,
         field_lines: read_field_line_objs(&addresses)?,
         world_models: read_world_models(&addresses)?,
-        battle_allies: read_battle_allies(&addresses)?,
+        battle_allies: read_battle_allies(&addresses, &read_battle_ally_equipment(&addresses, kernel)?)?,
         battle_enemies: read_battle_enemies(&addresses)?,
         field_data: read_field_data(&addresses)?,
         field_encounters: read_field_encounters(&addresses)?,
@@ -0,0 +1,94 @@
+use crate::ff7::types::battle::{EnemyMatch, ItemSource, SceneDatabase, SceneEnemy};
+use crate::ff7::types::kernel::{ElementalEffect, ElementalType};
+use std::collections::BTreeMap;
+
+/// Runs `predicate` on every enemy definition and groups the matches by enemy id, sorted by id
+pub fn find_enemies(database: &SceneDatabase, predicate: impl Fn(&SceneEnemy) -> bool) -> Vec<EnemyMatch> {
+    let mut matches: BTreeMap<u16, EnemyMatch> = BTreeMap::new();
+    for scene in &database.scenes {
+        for enemy in scene.enemies.iter().filter(|enemy| enemy.id != 0xFFFF && predicate(enemy)) {
+            let entry = matches.entry(enemy.id).or_insert_with(|| EnemyMatch {
+                enemy_id: enemy.id,
+                name: enemy.name.clone(),
+                battle_ids: Vec::new(),
+            });
+            for (formation_index, formation) in scene.formations.iter().enumerate() {
+                let battle_id = SceneDatabase::battle_id(scene.id, formation_index as u8);
+                if formation.entries().any(|entry| entry.enemy_id == enemy.id) && !entry.battle_ids.contains(&battle_id) {
+                    entry.battle_ids.push(battle_id);
+                }
+            }
+        }
+    }
+    matches.into_values().collect()
+}
+
+pub fn enemies_with_item(database: &SceneDatabase, item_id: u16, source: ItemSource) -> Vec<EnemyMatch> {
+    find_enemies(database, |enemy| match source {
+        ItemSource::Morph => enemy.morph_item_id == item_id,
+        ItemSource::Steal | ItemSource::Drop => enemy.items.iter().any(|item| {
+            item.item_id == item_id && item.is_steal() == (source == ItemSource::Steal)
+        }),
+    })
+}
+
+/// Empty element rate slots are never matched
+pub fn enemies_with_element_rate(database: &SceneDatabase, element: ElementalType, effect: ElementalEffect) -> Vec<EnemyMatch> {
+    if element == ElementalType::Nothing || effect == ElementalEffect::Nothing {
+        return Vec::new();
+    }
+    find_enemies(database, |enemy| {
+        enemy.element_rates.iter().any(|rate| rate.element_type == element as u8 && rate.rate == effect as u8)
+    })
+}
+
+pub fn enemies_weak_to(database: &SceneDatabase, element: ElementalType) -> Vec<EnemyMatch> {
+    enemies_with_element_rate(database, element, ElementalEffect::DoubleDamage)
+}
+
+pub fn enemies_absorbing(database: &SceneDatabase, element: ElementalType) -> Vec<EnemyMatch> {
+    enemies_with_element_rate(database, element, ElementalEffect::Absorb)
+}
+
+/// Enemies that have the attack in their attack list, e.g. Enemy Skill sources
+pub fn enemies_with_attack(database: &SceneDatabase, attack_id: u16) -> Vec<EnemyMatch> {
+    find_enemies(database, |enemy| enemy.enemy_attack_ids.contains(&attack_id))
+}
+
+/// Battle ids of every formation fought at `battle_location`
+pub fn formations_at_location(database: &SceneDatabase, battle_location: u16) -> Vec<u16> {
+    database
+        .scenes
+        .iter()
+        .flat_map(|scene| {
+            scene
+                .formations
+                .iter()
+                .enumerate()
+                .filter(|(_, formation)| formation.setup.battle_location == battle_location && formation.entries().next().is_some())
+                .map(|(formation_index, _)| SceneDatabase::battle_id(scene.id, formation_index as u8))
+        })
+        .collect()
+}
+
+/// Enemies appearing in formations at `battle_location`; battle ids are limited to that location
+pub fn enemies_at_location(database: &SceneDatabase, battle_location: u16) -> Vec<EnemyMatch> {
+    let battle_ids = formations_at_location(database, battle_location);
+    let mut matches: BTreeMap<u16, EnemyMatch> = BTreeMap::new();
+    for battle_id in battle_ids {
+        let Some(details) = database.formation_details(battle_id) else {
+            continue;
+        };
+        for enemy in details.enemies.iter().filter_map(|formation_enemy| formation_enemy.enemy.as_ref()) {
+            let entry = matches.entry(enemy.id).or_insert_with(|| EnemyMatch {
+                enemy_id: enemy.id,
+                name: enemy.name.clone(),
+                battle_ids: Vec::new(),
+            });
+            if !entry.battle_ids.contains(&battle_id) {
+                entry.battle_ids.push(battle_id);
+            }
+        }
+    }
+    matches.into_values().collect()
+}
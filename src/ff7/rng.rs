@@ -0,0 +1,67 @@
+use crate::ff7::addresses::FF7Addresses;
+use crate::ff7::types::general::{EncounterRng, RandomTable};
+use crate::ff7::types::kernel::BattleGrowthData;
+use crate::utils::memory::*;
+
+pub const RANDOM_TABLE_SIZE: usize = 256;
+
+/// Added to the step offset every time the step id wraps around
+const STEP_OFFSET_INCREMENT: u8 = 13;
+
+/// The game's current table index isn't read from memory yet, its address hasn't been
+/// verified, so `index` has to come from the caller.
+pub fn random_table(values: &[u8], index: u8) -> Result<RandomTable, String> {
+    if values.len() != RANDOM_TABLE_SIZE {
+        return Err(format!("Random table must have {} entries, got {}", RANDOM_TABLE_SIZE, values.len()));
+    }
+    Ok(RandomTable { values: values.to_vec(), index })
+}
+
+/// The random table shipped in kernel.bin's battle and growth section
+pub fn random_table_from_kernel(growth: &BattleGrowthData, index: u8) -> Result<RandomTable, String> {
+    random_table(&growth.random_table, index)
+}
+
+/// Returns the value at the current index and moves the index forward, wrapping at 256.
+/// The battle engine's own RNG is not modelled: neither its algorithm nor the address of its
+/// state has been verified against the game.
+pub fn next_random(table: &mut RandomTable) -> u8 {
+    let value = table.values[table.index as usize];
+    table.index = table.index.wrapping_add(1);
+    value
+}
+
+pub fn encounter_random(rng: &EncounterRng, table: &RandomTable) -> u8 {
+    table.values[rng.step_id.wrapping_add(rng.step_offset) as usize]
+}
+
+/// Moves the counters one step forward and adds `danger_increment` to the danger value
+pub fn advance_step(rng: &mut EncounterRng, danger_increment: u32) {
+    rng.step_id = rng.step_id.wrapping_add(1);
+    if rng.step_id == 0 {
+        rng.step_offset = rng.step_offset.wrapping_add(STEP_OFFSET_INCREMENT);
+    }
+    rng.danger_value = rng.danger_value.saturating_add(danger_increment);
+}
+
+/// An encounter triggers once the danger value is higher than the roll scaled to 16 bits
+pub fn encounter_triggers(rng: &EncounterRng, table: &RandomTable) -> bool {
+    (encounter_random(rng, table) as u32) << 8 < rng.danger_value
+}
+
+pub fn read_encounter_rng(addresses: &FF7Addresses) -> Result<EncounterRng, String> {
+    Ok(EncounterRng {
+        step_id: read_memory_int(addresses.step_id)? as u8,
+        step_offset: read_memory_int(addresses.step_offset)? as u8,
+        step_fraction: read_memory_int(addresses.step_fraction)?,
+        danger_value: read_memory_int(addresses.danger_value)?,
+    })
+}
+
+pub fn write_encounter_rng(addresses: &FF7Addresses, rng: &EncounterRng) -> Result<(), String> {
+    write_memory_int(addresses.step_id, rng.step_id as u32)?;
+    write_memory_int(addresses.step_offset, rng.step_offset as u32)?;
+    write_memory_int(addresses.step_fraction, rng.step_fraction)?;
+    write_memory_int(addresses.danger_value, rng.danger_value)?;
+    Ok(())
+}
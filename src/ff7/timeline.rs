@@ -0,0 +1,139 @@
+use crate::ff7::addresses::FF7Addresses;
+use crate::ff7::data::battle::{read_battle_allies, read_battle_enemies, read_battle_queue};
+use crate::ff7::types::battle::{
+    BattleAllyEquipment, BattleCharObj, BattleEvent, BattleQueuedAction, BattleSnapshot, BattleTimelineEntry,
+};
+use crate::utils::memory::read_memory_int;
+use std::io::Write;
+
+pub fn read_battle_snapshot(addresses: &FF7Addresses, equipment: &[BattleAllyEquipment]) -> Result<BattleSnapshot, String> {
+    let mut actors = read_battle_allies(addresses, equipment)?;
+    actors.extend(read_battle_enemies(addresses)?);
+    Ok(BattleSnapshot {
+        in_game_time: read_memory_int(addresses.in_game_time)?,
+        actors,
+        queue: read_battle_queue(addresses)?,
+    })
+}
+
+fn same_action(a: &BattleQueuedAction, b: &BattleQueuedAction) -> bool {
+    a.actor_index == b.actor_index && a.command_id == b.command_id && a.attack_id == b.attack_id && a.target_mask == b.target_mask
+}
+
+fn actor_events(previous: &BattleCharObj, current: &BattleCharObj) -> Vec<BattleEvent> {
+    let mut events = Vec::new();
+    let actor_index = current.index;
+    let name = || current.name.clone();
+
+    if previous.hp != current.hp {
+        events.push(BattleEvent::HpChange {
+            actor_index,
+            name: name(),
+            from: previous.hp,
+            to: current.hp,
+            delta: current.hp as i64 - previous.hp as i64,
+        });
+        if previous.hp > 0 && current.hp == 0 {
+            events.push(BattleEvent::KnockedOut { actor_index, name: name() });
+        } else if previous.hp == 0 && current.hp > 0 {
+            events.push(BattleEvent::Revived { actor_index, name: name() });
+        }
+    }
+    if previous.mp != current.mp {
+        events.push(BattleEvent::MpChange {
+            actor_index,
+            name: name(),
+            from: previous.mp,
+            to: current.mp,
+            delta: current.mp as i32 - previous.mp as i32,
+        });
+    }
+    if previous.status != current.status {
+        events.push(BattleEvent::StatusChange {
+            actor_index,
+            name: name(),
+            added: current.status.difference(previous.status).names(),
+            removed: previous.status.difference(current.status).names(),
+        });
+    }
+    events
+}
+
+/// Events between two consecutive snapshots. An action counts as started once it leaves the
+/// queue, with queued actions matched one to one so identical entries are told apart; actors
+/// are matched by index and empty slots (max HP 0) are skipped.
+pub fn diff_snapshots(previous: &BattleSnapshot, current: &BattleSnapshot) -> Vec<BattleEvent> {
+    let mut still_queued: Vec<&BattleQueuedAction> = current.queue.iter().collect();
+    let mut events = Vec::new();
+    for action in &previous.queue {
+        if let Some(position) = still_queued.iter().position(|queued| same_action(action, queued)) {
+            still_queued.remove(position);
+            continue;
+        }
+        events.push(BattleEvent::ActionStart {
+            actor_index: action.actor_index,
+            command_id: action.command_id,
+            attack_id: action.attack_id,
+            command_name: action.command_name.clone(),
+            attack_name: action.attack_name.clone(),
+            targets: action.targets(),
+        });
+    }
+
+    for actor in current.actors.iter().filter(|actor| actor.max_hp > 0) {
+        if let Some(before) = previous.actors.iter().find(|before| before.index == actor.index) {
+            events.extend(actor_events(before, actor));
+        }
+    }
+    events
+}
+
+/// Writes one JSON Lines log per battle: call `sample` (or `record` with a snapshot) every
+/// frame and `finish` when the battle ends.
+pub struct BattleRecorder<W: Write> {
+    writer: W,
+    battle_id: u16,
+    equipment: Vec<BattleAllyEquipment>, // Read once at the start of the battle
+    previous: Option<BattleSnapshot>,
+}
+
+impl<W: Write> BattleRecorder<W> {
+    /// `equipment` comes from `read_battle_ally_equipment` and is reused for every sample
+    pub fn new(writer: W, battle_id: u16, equipment: Vec<BattleAllyEquipment>) -> Self {
+        BattleRecorder { writer, battle_id, equipment, previous: None }
+    }
+
+    fn write_entry(&mut self, in_game_time: u32, event: BattleEvent) -> Result<BattleTimelineEntry, String> {
+        let entry = BattleTimelineEntry { in_game_time, event };
+        serde_json::to_writer(&mut self.writer, &entry).map_err(|e| e.to_string())?;
+        self.writer.write_all(b"\n").map_err(|e| e.to_string())?;
+        Ok(entry)
+    }
+
+    /// Logs and returns the events since the previous snapshot
+    pub fn record(&mut self, snapshot: BattleSnapshot) -> Result<Vec<BattleTimelineEntry>, String> {
+        let events = match &self.previous {
+            Some(previous) => diff_snapshots(previous, &snapshot),
+            None => vec![BattleEvent::BattleStart { battle_id: self.battle_id }],
+        };
+        let entries = events
+            .into_iter()
+            .map(|event| self.write_entry(snapshot.in_game_time, event))
+            .collect::<Result<Vec<_>, String>>()?;
+        self.previous = Some(snapshot);
+        Ok(entries)
+    }
+
+    /// Reads the live actors and battle queue and records them
+    pub fn sample(&mut self, addresses: &FF7Addresses) -> Result<Vec<BattleTimelineEntry>, String> {
+        let snapshot = read_battle_snapshot(addresses, &self.equipment)?;
+        self.record(snapshot)
+    }
+
+    pub fn finish(mut self, in_game_time: u32) -> Result<W, String> {
+        let battle_id = self.battle_id;
+        self.write_entry(in_game_time, BattleEvent::BattleEnd { battle_id })?;
+        self.writer.flush().map_err(|e| e.to_string())?;
+        Ok(self.writer)
+    }
+}
@@ -0,0 +1,36 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum AIOperand {
+    None,
+    Address(u16),
+    Value(u32),
+    Jump(u16),
+    Text(Vec<u8>),
+    DebugText { arg_count: u8, text: Vec<u8> },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AIInstruction {
+    pub offset: u16, // Relative to the start of the script, same base as jump targets
+    pub opcode: u8,
+    pub mnemonic: &'static str,
+    pub operand: AIOperand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AIVariableBank {
+    Local,  // 0x0000-0x1FFF: per script owner
+    Global, // 0x2000-0x3FFF: battle-wide values and actor masks
+    Actor,  // 0x4000+: per-actor attributes, read as a mask-indexed array
+}
+
+impl AIVariableBank {
+    pub fn from_address(address: u16) -> Self {
+        match address {
+            0x0000..=0x1FFF => AIVariableBank::Local,
+            0x2000..=0x3FFF => AIVariableBank::Global,
+            _ => AIVariableBank::Actor,
+        }
+    }
+}
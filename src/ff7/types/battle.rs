@@ -1,13 +1,17 @@
 use super::items::Item;
-use super::kernel::Elemental;
+use super::kernel::{named_flags, AttackData, Elemental, ElementalEffect, ElementDefense, ElementFlags, ItemData, StatusFlags};
+use bitflags::bitflags;
 use serde::Serialize;
 
-#[derive(Serialize)]
+/// Live battle actor, read from the actor record. The command menu and the per-status timers
+/// are kept outside that record and aren't read yet.
+#[derive(Debug, Clone, Serialize)]
 pub struct BattleCharObj {
     pub index: u8,
     pub name: String,
     pub flags: u8,
-    pub status: u32,
+    pub status: StatusFlags,
+    pub initial_status: StatusFlags,
     pub hp: u32,
     pub max_hp: u32,
     pub mp: u16,
@@ -15,6 +19,53 @@ pub struct BattleCharObj {
     pub atb: u16,
     pub limit: u16,
     pub scene_id: u8,
+    pub level: u8,
+    pub character_id: u8,
+    pub strength: u8, // Physical attack
+    pub weapon_attack: u8, // Attack of the equipped weapon, 0 for enemies
+    pub magic: u8, // Magic attack
+    pub dexterity: u8,
+    pub luck: u8,
+    pub defense: u16,
+    pub magic_defense: u16,
+    pub evade: u8,
+    pub magic_evade: u8,
+    pub weak_elements: ElementFlags,
+    pub resisted_elements: ElementFlags,
+    pub nullified_elements: ElementFlags,
+    pub absorbed_elements: ElementFlags,
+    pub back_damage_multiplier: u8,
+    pub row: u8, // 0 = front row
+    pub last_covered: u8, // Index of the last actor this one covered
+    pub last_targets: u16, // Actor mask
+    pub last_attacker: u16, // Actor mask
+    pub gil: u16,
+    pub exp: u32,
+}
+
+/// What a battle ally's equipment adds to its actor record; it can't change during a battle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BattleAllyEquipment {
+    pub weapon_attack: u8,
+    pub elements: ElementDefense,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BattleQueuedAction {
+    pub priority: u8, // Lower values act first
+    pub queue_position: u8,
+    pub actor_index: u8, // Same numbering as `BattleCharObj::index`
+    pub command_id: u8,
+    pub attack_id: u16,
+    pub target_mask: u16, // Bit n = actor n
+    pub command_name: Option<String>,
+    pub attack_name: Option<String>,
+}
+
+impl BattleQueuedAction {
+    pub fn targets(&self) -> Vec<u8> {
+        (0..16).filter(|bit| self.target_mask & (1 << bit) != 0).collect()
+    }
 }
 
 #[derive(Serialize)]
@@ -29,7 +80,7 @@ pub struct EnemyData {
     pub magic_defense: u16,
     pub elements: Vec<Elemental>,
     pub items: Vec<Item>,
-    pub status_immunities: u32,
+    pub status_immunities: StatusFlags,
     pub gil: u32,
     pub exp: u32,
     pub ap: u16,
@@ -47,14 +98,170 @@ pub struct SceneCameraPosition {
     pub z_dir: i16,
 }
 
+bitflags! {
+    /// Battle setup flags. Bits are cleared to turn a feature off; bits without a name are kept as is.
+    ///
+    /// Still unknown: 0x0001 and 0x0020-0x8000, which are set in every formation of the original scene.bin.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct BattleSetupFlags: u16 {
+        /// Shows the battle timer. Unlike the other flags this one is set to turn the feature on,
+        /// the original scene.bin only sets it for the follow-up formations of multi-part bosses
+        /// (Ying/Yang, Schizo, Carry Armor, Proud Clod) and the dummy scenes.
+        const SHOW_TIMER = 0x0002;
+        const ESCAPABLE = 0x0004;
+        const VICTORY_POSE = 0x0008; // Cleared for battles that end in a cutscene
+        const PREEMPTIVE = 0x0010; // Cleared when the party can't get a pre-emptive strike
+        const _ = !0;
+    }
+}
+
+/// Battle setup bit names, unknown bits are named by their value
+pub const BATTLE_SETUP_FLAG_NAMES: [&str; 16] = [
+    "Unknown 0x0001", "Show Timer", "Escapable", "Victory Pose",
+    "Pre-emptive", "Unknown 0x0020", "Unknown 0x0040", "Unknown 0x0080",
+    "Unknown 0x0100", "Unknown 0x0200", "Unknown 0x0400", "Unknown 0x0800",
+    "Unknown 0x1000", "Unknown 0x2000", "Unknown 0x4000", "Unknown 0x8000",
+];
+
+named_flags!(BattleSetupFlags, BATTLE_SETUP_FLAG_NAMES);
+
+impl BattleSetupFlags {
+    pub fn is_escapable(&self) -> bool {
+        self.contains(BattleSetupFlags::ESCAPABLE)
+    }
+
+    pub fn no_victory_pose(&self) -> bool {
+        !self.contains(BattleSetupFlags::VICTORY_POSE)
+    }
+
+    pub fn no_preemptive(&self) -> bool {
+        !self.contains(BattleSetupFlags::PREEMPTIVE)
+    }
+
+    pub fn show_timer(&self) -> bool {
+        self.contains(BattleSetupFlags::SHOW_TIMER)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BattleLayout {
+    Normal,
+    Preemptive,
+    BackAttack,
+    SideAttack,
+    PincerAttack,
+    PincerAttack2,
+    SideAttack2,
+    SideAttack3,
+    FrontRowLocked, // Normal battle with the party locked in the front row, Change is disabled
+    Unknown(u8),
+}
+
+impl BattleLayout {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BattleLayout::Normal => "Normal",
+            BattleLayout::Preemptive => "Pre-emptive",
+            BattleLayout::BackAttack => "Back Attack",
+            BattleLayout::SideAttack => "Side Attack",
+            BattleLayout::PincerAttack => "Pincer Attack",
+            BattleLayout::PincerAttack2 => "Pincer Attack 2",
+            BattleLayout::SideAttack2 => "Side Attack 2",
+            BattleLayout::SideAttack3 => "Side Attack 3",
+            BattleLayout::FrontRowLocked => "Front Row Locked",
+            BattleLayout::Unknown(_) => "Unknown",
+        }
+    }
+}
+
+impl From<u8> for BattleLayout {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => BattleLayout::Normal,
+            1 => BattleLayout::Preemptive,
+            2 => BattleLayout::BackAttack,
+            3 => BattleLayout::SideAttack,
+            4 => BattleLayout::PincerAttack,
+            5 => BattleLayout::PincerAttack2,
+            6 => BattleLayout::SideAttack2,
+            7 => BattleLayout::SideAttack3,
+            8 => BattleLayout::FrontRowLocked,
+            other => BattleLayout::Unknown(other),
+        }
+    }
+}
+
+impl From<BattleLayout> for u8 {
+    fn from(layout: BattleLayout) -> Self {
+        match layout {
+            BattleLayout::Normal => 0,
+            BattleLayout::Preemptive => 1,
+            BattleLayout::BackAttack => 2,
+            BattleLayout::SideAttack => 3,
+            BattleLayout::PincerAttack => 4,
+            BattleLayout::PincerAttack2 => 5,
+            BattleLayout::SideAttack2 => 6,
+            BattleLayout::SideAttack3 => 7,
+            BattleLayout::FrontRowLocked => 8,
+            BattleLayout::Unknown(value) => value,
+        }
+    }
+}
+
+bitflags! {
+    /// Columns a formation enemy covers: enemies behind it in the same column can't be hit by short range attacks
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct CoverFlags: u16 {
+        const COLUMN_1 = 0x0001;
+        const COLUMN_2 = 0x0002;
+        const COLUMN_3 = 0x0004;
+        const COLUMN_4 = 0x0008;
+        const COLUMN_5 = 0x0010;
+        const _ = !0;
+    }
+}
+
+bitflags! {
+    /// State a formation enemy starts the battle in. Normal enemies have every bit set.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct InitialConditionFlags: u32 {
+        const VISIBLE = 0x0001;
+        const SIDE_ATTACK_DIRECTION = 0x0002; // Which side the enemy faces in side attacks
+        const TARGETABLE = 0x0008;
+        const MAIN_SCRIPT_ACTIVE = 0x0010;
+        const _ = !0;
+    }
+}
+
+pub const COVER_FLAG_NAMES: [&str; 16] = [
+    "Column 1", "Column 2", "Column 3", "Column 4",
+    "Column 5", "Unknown 0x0020", "Unknown 0x0040", "Unknown 0x0080",
+    "Unknown 0x0100", "Unknown 0x0200", "Unknown 0x0400", "Unknown 0x0800",
+    "Unknown 0x1000", "Unknown 0x2000", "Unknown 0x4000", "Unknown 0x8000",
+];
+
+pub const INITIAL_CONDITION_FLAG_NAMES: [&str; 32] = [
+    "Visible", "Side Attack Direction", "Unknown 0x00000004", "Targetable",
+    "Main Script Active", "Unknown 0x00000020", "Unknown 0x00000040", "Unknown 0x00000080",
+    "Unknown 0x00000100", "Unknown 0x00000200", "Unknown 0x00000400", "Unknown 0x00000800",
+    "Unknown 0x00001000", "Unknown 0x00002000", "Unknown 0x00004000", "Unknown 0x00008000",
+    "Unknown 0x00010000", "Unknown 0x00020000", "Unknown 0x00040000", "Unknown 0x00080000",
+    "Unknown 0x00100000", "Unknown 0x00200000", "Unknown 0x00400000", "Unknown 0x00800000",
+    "Unknown 0x01000000", "Unknown 0x02000000", "Unknown 0x04000000", "Unknown 0x08000000",
+    "Unknown 0x10000000", "Unknown 0x20000000", "Unknown 0x40000000", "Unknown 0x80000000",
+];
+
+named_flags!(CoverFlags, COVER_FLAG_NAMES);
+named_flags!(InitialConditionFlags, INITIAL_CONDITION_FLAG_NAMES);
+
 #[derive(Debug, Clone, Copy, Serialize)]
 pub struct SceneBattleSetup {
     pub battle_location: u16,
     pub next_formation_id_on_win: u16,
     pub escape_counter: u16,
     pub next_battle_arena_candidates: [u16; 4],
-    pub flags: u16, // Includes escapable flag
-    pub battle_layout_type: u8,
+    pub flags: BattleSetupFlags,
+    pub battle_layout_type: BattleLayout,
     pub pre_battle_camera_pos_index: u8,
 }
 
@@ -71,8 +278,8 @@ pub struct SceneBattleFormationEntry {
     pub pos_y: i16,
     pub pos_z: i16,
     pub row: u16,
-    pub cover_flags: u16,
-    pub initial_condition_flags: u32,
+    pub cover_flags: CoverFlags,
+    pub initial_condition_flags: InitialConditionFlags,
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -87,6 +294,20 @@ pub struct SceneEnemyItem {
     pub item_id: u16,
 }
 
+/// Set in the rate byte of steal slots, clear for drops
+const ITEM_STEAL_FLAG: u8 = 0x80;
+
+impl SceneEnemyItem {
+    pub fn is_steal(&self) -> bool {
+        self.rate & ITEM_STEAL_FLAG != 0
+    }
+
+    /// Rate out of 64, without the steal flag
+    pub fn rate(&self) -> u8 {
+        self.rate & !ITEM_STEAL_FLAG
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SceneEnemy {
     pub id: u16,
@@ -100,6 +321,7 @@ pub struct SceneEnemy {
     pub magic: u8,
     pub magic_defense: u8,
     pub element_rates: [SceneElementRate; 8],
+    pub action_animation_indexes: [u8; 16],
     pub enemy_attack_ids: [u16; 16],
     pub enemy_attack_camera_movement_ids: [u16; 16],
     pub items: [SceneEnemyItem; 4],
@@ -111,48 +333,417 @@ pub struct SceneEnemy {
     pub hp: u32,
     pub exp: u32,
     pub gil: u32,
-    pub status_immunities: u32,
+    pub status_immunities: StatusFlags,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SceneAttack {
     pub id: u16,
     pub name: String, // Decoded name
+    pub data: AttackData,
 }
 
-// --- Stubbed Structures ---
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum SceneAIEvent {
+    Init,
+    Main,
+    GeneralCounter,
+    DeathCounter,
+    PhysicalCounter,
+    MagicalCounter,
+    BattleVictory,
+    PreActionSetup,
+    CustomEvent1,
+    CustomEvent2,
+    CustomEvent3,
+    CustomEvent4,
+    CustomEvent5,
+    CustomEvent6,
+    CustomEvent7,
+    CustomEvent8,
+}
 
-// #[derive(Debug, Clone, Copy, Serialize)]
-// pub struct SceneAttackData {
-//     pub data: [u8; 28],
-// }
+impl SceneAIEvent {
+    pub const ALL: [SceneAIEvent; 16] = [
+        SceneAIEvent::Init,
+        SceneAIEvent::Main,
+        SceneAIEvent::GeneralCounter,
+        SceneAIEvent::DeathCounter,
+        SceneAIEvent::PhysicalCounter,
+        SceneAIEvent::MagicalCounter,
+        SceneAIEvent::BattleVictory,
+        SceneAIEvent::PreActionSetup,
+        SceneAIEvent::CustomEvent1,
+        SceneAIEvent::CustomEvent2,
+        SceneAIEvent::CustomEvent3,
+        SceneAIEvent::CustomEvent4,
+        SceneAIEvent::CustomEvent5,
+        SceneAIEvent::CustomEvent6,
+        SceneAIEvent::CustomEvent7,
+        SceneAIEvent::CustomEvent8,
+    ];
 
-// #[derive(Debug, Clone, Copy, Serialize)]
-// pub struct SceneAIHeader {
-//     pub script_offsets: [u16; 16],
-// }
+    pub fn name(&self) -> &'static str {
+        match self {
+            SceneAIEvent::Init => "Init",
+            SceneAIEvent::Main => "Main",
+            SceneAIEvent::GeneralCounter => "Counter - General",
+            SceneAIEvent::DeathCounter => "Counter - Death",
+            SceneAIEvent::PhysicalCounter => "Counter - Physical",
+            SceneAIEvent::MagicalCounter => "Counter - Magical",
+            SceneAIEvent::BattleVictory => "Battle Victory",
+            SceneAIEvent::PreActionSetup => "Pre-Action Setup",
+            SceneAIEvent::CustomEvent1 => "Custom Event 1",
+            SceneAIEvent::CustomEvent2 => "Custom Event 2",
+            SceneAIEvent::CustomEvent3 => "Custom Event 3",
+            SceneAIEvent::CustomEvent4 => "Custom Event 4",
+            SceneAIEvent::CustomEvent5 => "Custom Event 5",
+            SceneAIEvent::CustomEvent6 => "Custom Event 6",
+            SceneAIEvent::CustomEvent7 => "Custom Event 7",
+            SceneAIEvent::CustomEvent8 => "Custom Event 8",
+        }
+    }
+}
 
-// #[derive(Debug, Clone, Serialize)]
-// pub struct SceneAIData {
-//     pub data: Vec<u8>,
-// }
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SceneAIHeader {
+    pub script_offsets: [u16; 16], // Relative to the start of the header, 0xFFFF = no script
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneAIScript {
+    pub event: SceneAIEvent,
+    pub offset: u16, // Relative to the header; jump targets inside the bytecode are relative to the script start
+    pub bytecode: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneAIData {
+    pub header: SceneAIHeader,
+    pub scripts: Vec<SceneAIScript>,
+}
+
+impl SceneAIData {
+    pub fn script(&self, event: SceneAIEvent) -> Option<&SceneAIScript> {
+        self.scripts.iter().find(|script| script.event == event)
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SceneFormation {
     pub setup: SceneBattleSetup,
     pub camera_placement: SceneCameraPlacement,
-    pub enemies: Vec<SceneBattleFormationEntry>,
+    pub enemies: [Option<SceneBattleFormationEntry>; 6], // Indexed by formation slot, empty slots are None
+}
+
+impl SceneFormation {
+    /// Entries of the occupied slots, in slot order
+    pub fn entries(&self) -> impl Iterator<Item = &SceneBattleFormationEntry> {
+        self.enemies.iter().flatten()
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Scene {
+    pub id: u16, // Index in scene.bin; scenes that fail to load leave a gap instead of shifting this
     pub enemies: [SceneEnemy; 3],
     pub formations: [SceneFormation; 4],
-    // pub attack_data: [SceneAttackData; 32],
     pub attacks: Vec<SceneAttack>,
-    // pub formation_ai_script_offsets: [u16; 4],
-    // pub formation_ai_data: SceneAIData,
-    // pub enemy_ai_script_offsets: [u16; 3],
-    // pub enemy_ai_headers: [SceneAIHeader; 3],
-    // pub enemy_ai_data: SceneAIData,
-}
\ No newline at end of file
+    pub formation_ai: [Option<SceneAIData>; 4],
+    pub enemy_ai: [Option<SceneAIData>; 3], // Indexed like `enemies`
+    #[serde(skip)]
+    pub raw: Vec<u8>, // Decompressed scene as read; unknown and padding bytes are written back from here
+    #[serde(skip)]
+    pub compressed: Vec<u8>, // Gzip data as read from scene.bin; written back as is while the scene is unmodified
+}
+
+impl Scene {
+    /// Finds the enemy definition used by a formation entry
+    pub fn enemy(&self, enemy_id: u16) -> Option<&SceneEnemy> {
+        self.enemies.iter().find(|enemy| enemy.id == enemy_id && enemy_id != 0xFFFF)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormationEnemy {
+    pub slot: u8, // Formation slot; enemy actors are numbered from 4 in slot order, skipping empty slots
+    pub entry: SceneBattleFormationEntry, // Position, row and initial flags
+    pub enemy: Option<SceneEnemy>, // Definition from the same scene
+    pub actor: Option<BattleCharObj>, // Live battle state, only filled while the battle is running
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormationDetails {
+    pub battle_id: u16,
+    pub scene_id: u16,
+    pub formation_index: u8,
+    pub setup: SceneBattleSetup,
+    pub camera_placement: SceneCameraPlacement,
+    pub enemies: Vec<FormationEnemy>,
+}
+
+/// All scenes from scene.bin, addressed by battle id: every scene holds 4 formations,
+/// so battle id N is formation N % 4 of the scene whose `id` is N / 4.
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneDatabase {
+    pub scenes: Vec<Scene>,
+}
+
+impl SceneDatabase {
+    pub fn new(scenes: Vec<Scene>) -> Self {
+        SceneDatabase { scenes }
+    }
+
+    pub fn battle_id(scene_id: u16, formation_index: u8) -> u16 {
+        scene_id * 4 + formation_index as u16
+    }
+
+    pub fn scene(&self, scene_id: u16) -> Option<&Scene> {
+        self.scenes.iter().find(|scene| scene.id == scene_id)
+    }
+
+    pub fn scene_for_battle(&self, battle_id: u16) -> Option<&Scene> {
+        self.scene(battle_id / 4)
+    }
+
+    pub fn formation(&self, battle_id: u16) -> Option<&SceneFormation> {
+        self.scene_for_battle(battle_id)
+            .map(|scene| &scene.formations[battle_id as usize % 4])
+    }
+
+    /// Formation joined with the enemy definitions of its scene
+    pub fn formation_details(&self, battle_id: u16) -> Option<FormationDetails> {
+        let scene = self.scene_for_battle(battle_id)?;
+        let formation = self.formation(battle_id)?;
+        Some(FormationDetails {
+            battle_id,
+            scene_id: battle_id / 4,
+            formation_index: (battle_id % 4) as u8,
+            setup: formation.setup,
+            camera_placement: formation.camera_placement,
+            enemies: formation
+                .enemies
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, entry)| {
+                    entry.as_ref().map(|entry| FormationEnemy {
+                        slot: slot as u8,
+                        entry: entry.clone(),
+                        enemy: scene.enemy(entry.enemy_id).cloned(),
+                        actor: None,
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Combat stats the damage formulas read from an attacker or a target
+#[derive(Debug, Clone, Serialize)]
+pub struct DamageActor {
+    pub level: u8,
+    pub attack: u16, // Strength plus weapon attack
+    pub magic: u16,
+    pub defense: u16,
+    pub magic_defense: u16,
+    pub hp: u32,
+    pub max_hp: u32,
+    pub status: StatusFlags,
+    pub back_row: bool,
+    pub back_damage_multiplier: u8, // In eighths
+    pub element_rates: Vec<SceneElementRate>,
+}
+
+impl From<&SceneEnemy> for DamageActor {
+    fn from(enemy: &SceneEnemy) -> Self {
+        DamageActor {
+            level: enemy.level,
+            attack: enemy.strength as u16,
+            magic: enemy.magic as u16,
+            // scene.bin stores half of the defense values the battle engine uses
+            defense: enemy.defense as u16 * 2,
+            magic_defense: enemy.magic_defense as u16 * 2,
+            hp: enemy.hp,
+            max_hp: enemy.hp,
+            status: StatusFlags::empty(),
+            back_row: false,
+            back_damage_multiplier: enemy.back_damage_multiplier,
+            element_rates: enemy.element_rates.to_vec(),
+        }
+    }
+}
+
+impl From<&BattleCharObj> for DamageActor {
+    fn from(actor: &BattleCharObj) -> Self {
+        let masks = [
+            (actor.weak_elements, ElementalEffect::DoubleDamage),
+            (actor.resisted_elements, ElementalEffect::HalfDamage),
+            (actor.nullified_elements, ElementalEffect::Nullify),
+            (actor.absorbed_elements, ElementalEffect::Absorb),
+        ];
+        let element_rates = masks
+            .iter()
+            .flat_map(|(elements, effect)| {
+                elements
+                    .elemental_types()
+                    .into_iter()
+                    .map(move |element| SceneElementRate { element_type: element as u8, rate: *effect as u8 })
+            })
+            .collect();
+        DamageActor {
+            level: actor.level,
+            attack: actor.strength as u16 + actor.weapon_attack as u16,
+            magic: actor.magic as u16,
+            defense: actor.defense,
+            magic_defense: actor.magic_defense,
+            hp: actor.hp,
+            max_hp: actor.max_hp,
+            status: actor.status,
+            back_row: actor.row != 0,
+            back_damage_multiplier: actor.back_damage_multiplier,
+            element_rates,
+        }
+    }
+}
+
+/// The parts of an attack, item or weapon that feed the damage formulas
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DamageAttack {
+    pub damage_func: u8, // Upper nibble: 1 = physical, 2 = magical; lower nibble: formula
+    pub power: u8,
+    pub attack_element: ElementFlags,
+}
+
+impl From<&AttackData> for DamageAttack {
+    fn from(attack: &AttackData) -> Self {
+        DamageAttack { damage_func: attack.damage_func, power: attack.power, attack_element: attack.attack_element }
+    }
+}
+
+impl From<&ItemData> for DamageAttack {
+    fn from(item: &ItemData) -> Self {
+        DamageAttack { damage_func: item.damage_func, power: item.power, attack_element: item.attack_element }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DamageContext {
+    pub critical: bool,
+    pub back_attack: bool, // Target is hit from behind
+    pub defending: bool, // Target used Defend
+    pub long_range: bool, // Ignores both actors' rows
+    pub split: bool, // Multi-target attack split across targets
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DamageEstimate {
+    pub min: i32, // Negative values heal the target
+    pub max: i32,
+    pub expected: f32,
+    pub instant_death: bool, // Target has the Death element rate for this attack
+}
+
+/// Live battle state sampled once per frame by the timeline recorder
+#[derive(Debug, Clone, Serialize)]
+pub struct BattleSnapshot {
+    pub in_game_time: u32, // Seconds
+    pub actors: Vec<BattleCharObj>, // Allies then enemies
+    pub queue: Vec<BattleQueuedAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BattleEvent {
+    BattleStart { battle_id: u16 },
+    ActionStart { actor_index: u8, command_id: u8, attack_id: u16, command_name: Option<String>, attack_name: Option<String>, targets: Vec<u8> },
+    HpChange { actor_index: u8, name: String, from: u32, to: u32, delta: i64 },
+    MpChange { actor_index: u8, name: String, from: u16, to: u16, delta: i32 },
+    StatusChange { actor_index: u8, name: String, added: Vec<&'static str>, removed: Vec<&'static str> },
+    KnockedOut { actor_index: u8, name: String },
+    Revived { actor_index: u8, name: String },
+    BattleEnd { battle_id: u16 },
+}
+
+/// One line of the JSON Lines battle log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BattleTimelineEntry {
+    pub in_game_time: u32,
+    #[serde(flatten)]
+    pub event: BattleEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BestiaryElementRate {
+    pub element: String,
+    pub effect: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BestiaryItem {
+    pub name: String,
+    pub rate: u8, // Out of 64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BestiaryFormation {
+    pub battle_id: u16,
+    pub scene_id: u16,
+    pub formation_index: u8,
+}
+
+/// One enemy id with the stats of its first scene.bin appearance and every formation it shows up in
+#[derive(Debug, Clone, Serialize)]
+pub struct BestiaryEntry {
+    pub id: u16,
+    pub name: String,
+    pub level: u8,
+    pub hp: u32,
+    pub mp: u16,
+    pub exp: u32,
+    pub ap: u16,
+    pub gil: u32,
+    pub strength: u8,
+    pub defense: u8,
+    pub magic: u8,
+    pub magic_defense: u8,
+    pub speed: u8,
+    pub luck: u8,
+    pub evade: u8,
+    pub element_rates: Vec<BestiaryElementRate>,
+    pub steal: Vec<BestiaryItem>,
+    pub drop: Vec<BestiaryItem>,
+    pub morph: Option<String>,
+    pub attacks: Vec<String>,
+    pub formations: Vec<BestiaryFormation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ItemSource {
+    Steal,
+    Drop,
+    Morph,
+}
+
+/// An enemy matched by a scene query, with the battle ids of every formation it appears in
+/// across the scenes where it matched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnemyMatch {
+    pub enemy_id: u16,
+    pub name: String,
+    pub battle_ids: Vec<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum SceneChangeKind {
+    Changed { old: String, new: String },
+    Added { value: String },
+    Removed { value: String },
+}
+
+/// One field-level difference between two versions of a scene
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SceneChange {
+    pub scene_id: u16,
+    pub path: String, // e.g. "enemies[0].hp" or "attacks[0x0150]"
+    pub change: SceneChangeKind,
+}
@@ -1,3 +1,4 @@
+use super::items::ItemChance;
 use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -67,8 +68,63 @@ pub struct FieldEncounterSet {
     pub pincer_attack: EncounterPair,          // length 1
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct FieldEncounterTables {
     pub table1: FieldEncounterSet,
     pub table2: FieldEncounterSet,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncounterKind {
+    Normal,
+    BackAttack,
+    SideAttack,
+    PincerAttack,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EncounterChance {
+    pub kind: EncounterKind,
+    pub slot: u8, // Index within the kind's slots
+    pub encounter_id: u16,
+    pub chance: f32, // 0.0-1.0
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EncounterPrediction {
+    pub walking_steps: Option<u32>, // None when the table can't trigger encounters
+    pub running_steps: Option<u32>,
+    pub formations: Vec<EncounterChance>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EncounterEnemyReport {
+    pub enemy_id: u16,
+    pub name: String,
+    pub level: u8,
+    pub hp: u32,
+    pub steal: Vec<ItemChance>,
+    pub drop: Vec<ItemChance>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EncounterSlotReport {
+    pub kind: EncounterKind,
+    pub slot: u8,
+    pub battle_id: u16,
+    pub percentage: f32, // Chance of this formation once an encounter triggers
+    pub enemies: Vec<EncounterEnemyReport>, // Empty when the battle id is not in scene.bin
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EncounterTableReport {
+    pub active: bool,
+    pub encounter_rate: u8,
+    pub slots: Vec<EncounterSlotReport>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EncounterReport {
+    pub table1: EncounterTableReport,
+    pub table2: EncounterTableReport,
+}
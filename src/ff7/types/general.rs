@@ -85,4 +85,21 @@ pub struct FF7BasicData {
     pub battle_points: u16,
     pub auto_sense_check: u8,
     pub field_run_by_default_check: u8,
-} 
\ No newline at end of file
+} 
+
+/// The game's 256-byte random table and the index of the next value to hand out
+#[derive(Serialize, Debug, Clone)]
+pub struct RandomTable {
+    pub values: Vec<u8>, // 256 entries
+    pub index: u8,
+}
+
+/// Field encounter counters. The encounter roll is
+/// `values[(step_id + step_offset) & 0xFF]` from the random table.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncounterRng {
+    pub step_id: u8,
+    pub step_offset: u8,
+    pub step_fraction: u32,
+    pub danger_value: u32,
+}
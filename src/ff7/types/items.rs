@@ -12,3 +12,19 @@ pub enum ItemType {
     Steal,
     Drop,
 }
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ItemChance {
+    pub slot: u8,
+    pub item_id: u16,
+    pub name: Option<String>,
+    pub chance: f32, // 0.0-1.0, already accounts for earlier slots being rolled first
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EnemyLoot {
+    pub enemy_id: u16,
+    pub steal: Vec<ItemChance>,
+    pub drop: Vec<ItemChance>,
+    pub morph: Option<ItemChance>, // Chance that a Morph hit kills the enemy
+}
@@ -1,3 +1,4 @@
+use bitflags::bitflags;
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -6,8 +7,7 @@ pub struct Elemental {
     pub effect: u8,
 }
 
-#[allow(dead_code)]
-#[derive(Serialize)]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElementalEffect {
     Death = 0,
     DoubleDamage = 2,
@@ -18,8 +18,34 @@ pub enum ElementalEffect {
     Nothing = 0xFF,
 }
 
-#[allow(dead_code)]
-#[derive(Serialize)]
+impl ElementalEffect {
+    /// Unknown rate codes map to `Nothing`
+    pub fn from_rate(rate: u8) -> Self {
+        match rate {
+            0 => ElementalEffect::Death,
+            2 => ElementalEffect::DoubleDamage,
+            4 => ElementalEffect::HalfDamage,
+            5 => ElementalEffect::Nullify,
+            6 => ElementalEffect::Absorb,
+            7 => ElementalEffect::FullCure,
+            _ => ElementalEffect::Nothing,
+        }
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            ElementalEffect::Death => Some("Death"),
+            ElementalEffect::DoubleDamage => Some("Double Damage"),
+            ElementalEffect::HalfDamage => Some("Half Damage"),
+            ElementalEffect::Nullify => Some("Nullify"),
+            ElementalEffect::Absorb => Some("Absorb"),
+            ElementalEffect::FullCure => Some("Full Cure"),
+            ElementalEffect::Nothing => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElementalType {
     Fire = 0,
     Ice,
@@ -40,11 +66,242 @@ pub enum ElementalType {
     Nothing = 0xFF,
 }
 
-#[derive(Serialize)]
+const ELEMENTAL_TYPES: [ElementalType; 16] = [
+    ElementalType::Fire, ElementalType::Ice, ElementalType::Bolt, ElementalType::Earth,
+    ElementalType::Bio, ElementalType::Gravity, ElementalType::Water, ElementalType::Wind,
+    ElementalType::Holy, ElementalType::Health, ElementalType::Cut, ElementalType::Hit,
+    ElementalType::Punch, ElementalType::Shoot, ElementalType::Scream, ElementalType::Hidden,
+];
+
+impl ElementalType {
+    /// Element index as stored in scene.bin element rates; anything past Hidden maps to `Nothing`
+    pub fn from_index(index: u8) -> Self {
+        ELEMENTAL_TYPES.get(index as usize).copied().unwrap_or(ElementalType::Nothing)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        ELEMENT_NAMES.get(*self as usize).copied()
+    }
+}
+
+/// Element names in `ElementalType` / attack element bit order
+pub const ELEMENT_NAMES: [&str; 16] = [
+    "Fire", "Ice", "Bolt", "Earth", "Bio", "Gravity", "Water", "Wind",
+    "Holy", "Health", "Cut", "Hit", "Punch", "Shoot", "Scream", "Hidden",
+];
+
+/// Status bits in the order the battle engine stores them
+pub const STATUS_NAMES: [&str; 32] = [
+    "Death", "Near-death", "Sleep", "Poison", "Sadness", "Fury", "Confusion", "Silence",
+    "Haste", "Slow", "Stop", "Frog", "Small", "Slow-numb", "Petrify", "Regen",
+    "Barrier", "MBarrier", "Reflect", "Dual", "Shield", "Death Sentence", "Manipulate", "Berserk",
+    "Peerless", "Paralysis", "Darkness", "Dual-Drain", "DeathForce", "Resist", "Lucky Girl", "Imprisoned",
+];
+
+/// Targeting bits shared by attacks, items, weapons and commands
+pub const TARGET_NAMES: [&str; 8] = [
+    "Enable Selection", "Start On Enemies", "Default Multiple", "Toggle Multiple",
+    "One Row Only", "Short Range", "All Rows", "Random Target",
+];
+
+bitflags! {
+    /// Status effects: actor statuses, immunities and the statuses an attack inflicts
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct StatusFlags: u32 {
+        const DEATH = 1 << 0;
+        const NEAR_DEATH = 1 << 1;
+        const SLEEP = 1 << 2;
+        const POISON = 1 << 3;
+        const SADNESS = 1 << 4;
+        const FURY = 1 << 5;
+        const CONFUSION = 1 << 6;
+        const SILENCE = 1 << 7;
+        const HASTE = 1 << 8;
+        const SLOW = 1 << 9;
+        const STOP = 1 << 10;
+        const FROG = 1 << 11;
+        const SMALL = 1 << 12;
+        const SLOW_NUMB = 1 << 13;
+        const PETRIFY = 1 << 14;
+        const REGEN = 1 << 15;
+        const BARRIER = 1 << 16;
+        const MBARRIER = 1 << 17;
+        const REFLECT = 1 << 18;
+        const DUAL = 1 << 19;
+        const SHIELD = 1 << 20;
+        const DEATH_SENTENCE = 1 << 21;
+        const MANIPULATE = 1 << 22;
+        const BERSERK = 1 << 23;
+        const PEERLESS = 1 << 24;
+        const PARALYSIS = 1 << 25;
+        const DARKNESS = 1 << 26;
+        const DUAL_DRAIN = 1 << 27;
+        const DEATH_FORCE = 1 << 28;
+        const RESIST = 1 << 29;
+        const LUCKY_GIRL = 1 << 30;
+        const IMPRISONED = 1 << 31;
+    }
+}
+
+bitflags! {
+    /// Element mask, one bit per `ElementalType`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ElementFlags: u16 {
+        const FIRE = 1 << 0;
+        const ICE = 1 << 1;
+        const BOLT = 1 << 2;
+        const EARTH = 1 << 3;
+        const BIO = 1 << 4;
+        const GRAVITY = 1 << 5;
+        const WATER = 1 << 6;
+        const WIND = 1 << 7;
+        const HOLY = 1 << 8;
+        const HEALTH = 1 << 9;
+        const CUT = 1 << 10;
+        const HIT = 1 << 11;
+        const PUNCH = 1 << 12;
+        const SHOOT = 1 << 13;
+        const SCREAM = 1 << 14;
+        const HIDDEN = 1 << 15;
+    }
+}
+
+bitflags! {
+    /// How an attack, item, weapon or command picks its targets
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct TargetFlags: u8 {
+        const ENABLE_SELECTION = 1 << 0;
+        const START_ON_ENEMIES = 1 << 1;
+        const DEFAULT_MULTIPLE = 1 << 2;
+        const TOGGLE_MULTIPLE = 1 << 3;
+        const ONE_ROW_ONLY = 1 << 4;
+        const SHORT_RANGE = 1 << 5;
+        const ALL_ROWS = 1 << 6;
+        const RANDOM_TARGET = 1 << 7;
+    }
+}
+
+/// Display names, `Display` and serde support for a flag type whose every bit is named.
+/// Flags serialize as a list of names, lowest bit first.
+macro_rules! named_flags {
+    ($flags:ident, $names:ident) => {
+        impl $flags {
+            pub fn names(&self) -> Vec<&'static str> {
+                $names
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| self.bits() & (1 << bit) != 0)
+                    .map(|(_, name)| *name)
+                    .collect()
+            }
+
+            /// Single flag by its display name, case insensitive
+            pub fn from_display_name(name: &str) -> Option<Self> {
+                $names
+                    .iter()
+                    .position(|known| known.eq_ignore_ascii_case(name))
+                    .map(|bit| Self::from_bits_retain(1 << bit))
+            }
+        }
+
+        impl std::fmt::Display for $flags {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if self.is_empty() {
+                    write!(f, "None")
+                } else {
+                    write!(f, "{}", self.names().join(", "))
+                }
+            }
+        }
+
+        impl serde::Serialize for $flags {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.names())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $flags {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <Vec<String> as serde::Deserialize>::deserialize(deserializer)?.iter().try_fold($flags::empty(), |flags, name| {
+                    $flags::from_display_name(name)
+                        .map(|flag| flags | flag)
+                        .ok_or_else(|| <D::Error as serde::de::Error>::custom(format!("Unknown {} name: {}", stringify!($flags), name)))
+                })
+            }
+        }
+    };
+}
+
+pub(crate) use named_flags;
+
+named_flags!(StatusFlags, STATUS_NAMES);
+named_flags!(ElementFlags, ELEMENT_NAMES);
+named_flags!(TargetFlags, TARGET_NAMES);
+
+impl From<ElementalType> for ElementFlags {
+    fn from(element: ElementalType) -> Self {
+        match element {
+            ElementalType::Nothing => ElementFlags::empty(),
+            element => ElementFlags::from_bits_retain(1 << element as u8),
+        }
+    }
+}
+
+impl ElementFlags {
+    pub fn elemental_types(&self) -> Vec<ElementalType> {
+        ELEMENTAL_TYPES.iter().copied().filter(|element| self.contains((*element).into())).collect()
+    }
+}
+
+/// Name of an `ElementalEffect` rate code
+pub fn elemental_effect_name(effect: u8) -> Option<&'static str> {
+    ElementalEffect::from_rate(effect).name()
+}
+
+/// Elements an actor takes double, half, no or absorbed damage from
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ElementDefense {
+    pub weak: ElementFlags,
+    pub resisted: ElementFlags,
+    pub nullified: ElementFlags,
+    pub absorbed: ElementFlags,
+}
+
+impl ElementDefense {
+    /// Builds the masks from scene.bin element rates. Death and Full Cure rates aren't
+    /// damage multipliers and are left out.
+    pub fn from_rates(rates: &[Elemental]) -> Self {
+        let mut defense = ElementDefense::default();
+        for rate in rates {
+            let element = ElementFlags::from(ElementalType::from_index(rate.element));
+            match ElementalEffect::from_rate(rate.effect) {
+                ElementalEffect::DoubleDamage => defense.weak |= element,
+                ElementalEffect::HalfDamage => defense.resisted |= element,
+                ElementalEffect::Nullify => defense.nullified |= element,
+                ElementalEffect::Absorb => defense.absorbed |= element,
+                _ => {}
+            }
+        }
+        defense
+    }
+
+    /// Adds an armor or accessory's `element_defense`. `mode` 0 absorbs, 1 nullifies
+    /// and 2 halves; anything else has no effect.
+    pub fn add_equipment(&mut self, mode: u8, elements: ElementFlags) {
+        match mode {
+            0 => self.absorbed |= elements,
+            1 => self.nullified |= elements,
+            2 => self.resisted |= elements,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
 pub struct ItemData {
     pub camera_move_id: u16,
     pub restriction_mask: u16,
-    pub target_flags: u8,
+    pub target_flags: TargetFlags,
     pub attack_effect_id: u8,
     pub damage_func: u8,
     pub power: u8,
@@ -52,7 +309,359 @@ pub struct ItemData {
     pub status_effect_change: u8,
     pub attack_additional_effect: u8,
     pub additional_effect_modifier: u8,
-    pub status_effects: u32,
-    pub attack_element: u16,
+    pub status_effects: StatusFlags,
+    pub attack_element: ElementFlags,
+    pub special_attack_flags: u16,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct CommandData {
+    pub initial_cursor_action: u8,
+    pub target_flags: TargetFlags,
+    pub camera_movement_single: u16,
+    pub camera_movement_multiple: u16,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct AttackData {
+    pub attack_percent: u8,
+    pub impact_effect_id: u8,
+    pub target_hurt_action_idx: u8,
+    pub mp_cost: u16,
+    pub impact_sound: u16,
+    pub camera_movement_single: u16,
+    pub camera_movement_multiple: u16,
+    pub target_flags: TargetFlags,
+    pub attack_effect_id: u8,
+    pub damage_func: u8,
+    pub power: u8,
+    pub condition: u8,
+    pub status_effect_change: u8,
+    pub attack_additional_effect: u8,
+    pub additional_effect_modifier: u8,
+    pub status_effects: StatusFlags,
+    pub attack_element: ElementFlags,
+    pub special_attack_flags: u16, // Stored inverted in the game files
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatBoost {
+    pub stat: u8, // 0=Strength, 1=Vitality, 2=Magic, 3=Spirit, 4=Dexterity, 5=Luck
+    pub value: u8,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MateriaSlotKind {
+    None,
+    Single,
+    LinkedLeft,
+    LinkedRight,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MateriaSlotLayout {
+    pub kind: MateriaSlotKind,
+    pub growth: bool, // Slots without growth hold materia but don't award AP
+}
+
+impl MateriaSlotLayout {
+    pub fn from_byte(value: u8) -> Self {
+        let kind = match value & 0x03 {
+            1 => MateriaSlotKind::Single,
+            2 => MateriaSlotKind::LinkedLeft,
+            3 => MateriaSlotKind::LinkedRight,
+            _ => MateriaSlotKind::None,
+        };
+        MateriaSlotLayout {
+            kind,
+            growth: kind != MateriaSlotKind::None && value & 0x04 != 0,
+        }
+    }
+
+    pub fn to_byte(&self) -> u8 {
+        let kind = match self.kind {
+            MateriaSlotKind::None => return 0,
+            MateriaSlotKind::Single => 1,
+            MateriaSlotKind::LinkedLeft => 2,
+            MateriaSlotKind::LinkedRight => 3,
+        };
+        if self.growth { kind | 0x04 } else { kind }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WeaponData {
+    pub target_flags: TargetFlags,
+    pub damage_func: u8,
+    pub attack: u8,
+    pub status_attack: u8, // Status set index, 0xFF = none
+    pub materia_growth_rate: u8,
+    pub critical_rate: u8,
+    pub hit_rate: u8,
+    pub model_id: u8,
+    pub camera_movement: u16,
+    pub equip_mask: u16, // One bit per character id
+    pub attack_element: ElementFlags,
+    pub stat_boosts: Vec<StatBoost>,
+    pub materia_slots: [MateriaSlotLayout; 8],
+    pub hit_sound: u8,
+    pub critical_sound: u8,
+    pub miss_sound: u8,
+    pub impact_effect_id: u8,
     pub special_attack_flags: u16,
-}
\ No newline at end of file
+    pub restriction_mask: u16,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArmorData {
+    pub element_defense_mode: u8, // How `element_defense` is applied: 0 = absorb, 1 = nullify, 2 = halve
+    pub defense: u8,
+    pub magic_defense: u8,
+    pub defense_rate: u8,
+    pub magic_defense_rate: u8,
+    pub status_defense: u8, // Status set index, 0xFF = none
+    pub materia_slots: [MateriaSlotLayout; 8],
+    pub materia_growth_rate: u8,
+    pub equip_mask: u16,
+    pub element_defense: ElementFlags,
+    pub stat_boosts: Vec<StatBoost>,
+    pub restriction_mask: u16,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AccessoryData {
+    pub stat_boosts: Vec<StatBoost>,
+    pub element_defense_mode: u8,
+    pub special_effect: u8,
+    pub element_defense: ElementFlags,
+    pub status_defense: StatusFlags,
+    pub equip_mask: u16,
+    pub restriction_mask: u16,
+}
+
+fn equip_mask_has(equip_mask: u16, character_id: u8) -> bool {
+    character_id < 16 && equip_mask & (1 << character_id) != 0
+}
+
+impl WeaponData {
+    pub fn can_equip(&self, character_id: u8) -> bool {
+        equip_mask_has(self.equip_mask, character_id)
+    }
+}
+
+impl ArmorData {
+    pub fn can_equip(&self, character_id: u8) -> bool {
+        equip_mask_has(self.equip_mask, character_id)
+    }
+}
+
+impl AccessoryData {
+    pub fn can_equip(&self, character_id: u8) -> bool {
+        equip_mask_has(self.equip_mask, character_id)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MateriaType {
+    Independent,
+    Support,
+    Magic,
+    Summon,
+    Command,
+}
+
+impl MateriaType {
+    /// The low nibble of the kernel type byte selects the base type (and materia color)
+    pub fn from_type_byte(value: u8) -> Self {
+        match value & 0x0F {
+            0x2 | 0x3 | 0x6 | 0x7 | 0x8 => MateriaType::Command,
+            0x5 => MateriaType::Support,
+            0x9 | 0xA => MateriaType::Magic,
+            0xB | 0xC => MateriaType::Summon,
+            _ => MateriaType::Independent,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MateriaType::Independent => "Independent",
+            MateriaType::Support => "Support",
+            MateriaType::Magic => "Magic",
+            MateriaType::Summon => "Summon",
+            MateriaType::Command => "Command",
+        }
+    }
+}
+
+/// Percentage (HP/MP) and flat (other stats) changes applied while a materia is equipped
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MateriaStatModifiers {
+    pub strength: i8,
+    pub vitality: i8,
+    pub magic: i8,
+    pub spirit: i8,
+    pub dexterity: i8,
+    pub luck: i8,
+    pub hp_percent: i8,
+    pub mp_percent: i8,
+}
+
+// Equip effect table hardcoded in the executable, indexed by `MateriaData::equip_effect`
+const MATERIA_EQUIP_EFFECTS: [[i8; 8]; 17] = [
+    [0, 0, 0, 0, 0, 0, 0, 0],
+    [-2, -1, 2, 1, 0, 0, -5, 5],
+    [-4, -2, 4, 2, 0, 0, -10, 10],
+    [0, 0, 0, 0, 2, -2, 0, 0],
+    [-1, -1, 1, 1, 0, 0, 0, 0],
+    [1, 1, -1, -1, 0, 0, 0, 0],
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 0, 1, 0, 0],
+    [0, 0, 0, 0, 0, -1, 0, 0],
+    [0, 0, 0, 0, -2, 0, 0, 0],
+    [0, 0, 0, 0, 2, 0, 0, 0],
+    [-1, 0, 1, 0, 0, 0, 2, -2],
+    [0, 0, 1, 0, 0, 0, -2, 2],
+    [0, 0, 1, 1, 0, 0, -5, 5],
+    [0, 0, 2, 2, 0, 0, -10, 10],
+    [0, 0, 4, 4, 0, 0, -10, 15],
+    [0, 0, 8, 8, 0, 0, -10, 20],
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MateriaData {
+    pub ap_thresholds: [u16; 4], // AP / 100 needed for levels 2-5, 0xFFFF = no such level
+    pub equip_effect: u8,
+    pub status_effects: StatusFlags, // Only the low 24 bits are stored
+    pub element: u8,
+    pub type_byte: u8,
+    pub materia_type: MateriaType,
+    pub subtype: u8,
+    pub abilities: [u8; 6], // Meaning depends on the materia type (spell ids, command ids, support effects)
+}
+
+impl MateriaData {
+    /// Total AP needed for each level, starting from level 2
+    pub fn level_ap(&self) -> Vec<u32> {
+        self.ap_thresholds
+            .iter()
+            .take_while(|&&t| t != 0xFFFF)
+            .map(|&t| t as u32 * 100)
+            .collect()
+    }
+
+    pub fn max_level(&self) -> u8 {
+        self.level_ap().len() as u8 + 1
+    }
+
+    pub fn level_for_ap(&self, ap: u32) -> u8 {
+        1 + self.level_ap().iter().filter(|&&needed| ap >= needed).count() as u8
+    }
+
+    pub fn stat_modifiers(&self) -> MateriaStatModifiers {
+        let m = MATERIA_EQUIP_EFFECTS
+            .get(self.equip_effect as usize)
+            .copied()
+            .unwrap_or_default();
+        MateriaStatModifiers {
+            strength: m[0],
+            vitality: m[1],
+            magic: m[2],
+            spirit: m[3],
+            dexterity: m[4],
+            luck: m[5],
+            hp_percent: m[6],
+            mp_percent: m[7],
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CharacterGrowthRecord {
+    pub strength_curve: u8,
+    pub vitality_curve: u8,
+    pub magic_curve: u8,
+    pub spirit_curve: u8,
+    pub dexterity_curve: u8,
+    pub luck_curve: u8,
+    pub hp_curve: u8,
+    pub mp_curve: u8,
+    pub exp_curve: u8,
+    pub limit_commands: [u8; 12],
+    pub limit_kills_required: [u16; 2], // Kills needed to unlock limit levels 2 and 3
+    pub limit_uses_required: [u16; 6],  // Uses needed to learn x-2 and x-3 limits for each level
+    pub limit_hp_divisors: [u32; 4],
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct StatCurve {
+    pub gradients: [u8; 8],
+    pub bases: [u8; 8],
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BattleGrowthData {
+    pub characters: Vec<CharacterGrowthRecord>, // Cloud..Cid, Young Cloud, Sephiroth
+    pub stat_random_bonus: [u8; 12],
+    pub hp_random_bonus: [u8; 12],
+    pub mp_random_bonus: [u8; 12],
+    pub stat_curves: Vec<StatCurve>, // 64 curves, one gradient/base pair per level bracket
+    pub character_ai: Vec<u8>,
+    pub random_table: Vec<u8>, // 256 bytes
+    pub scene_lookup_table: Vec<u8>, // 64 bytes
+    pub spell_order: Vec<u8>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct KernelTexts {
+    pub command_descriptions: Vec<String>,
+    pub magic_descriptions: Vec<String>,
+    pub item_descriptions: Vec<String>,
+    pub weapon_descriptions: Vec<String>,
+    pub armor_descriptions: Vec<String>,
+    pub accessory_descriptions: Vec<String>,
+    pub materia_descriptions: Vec<String>,
+    pub key_item_descriptions: Vec<String>,
+    pub command_names: Vec<String>,
+    pub magic_names: Vec<String>,
+    pub item_names: Vec<String>,
+    pub weapon_names: Vec<String>,
+    pub armor_names: Vec<String>,
+    pub accessory_names: Vec<String>,
+    pub materia_names: Vec<String>,
+    pub key_item_names: Vec<String>,
+    pub battle_texts: Vec<String>,
+    pub summon_attack_names: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct KernelSection {
+    pub file_type: u16,
+    pub data: Vec<u8>, // Decompressed section data
+    #[serde(skip)]
+    pub compressed: Vec<u8>, // Original gzip stream, reused when the section is left unmodified
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct KernelBin {
+    pub sections: Vec<KernelSection>,
+    pub commands: Vec<CommandData>,
+    pub attacks: Vec<AttackData>,
+    pub battle_growth: BattleGrowthData,
+    pub initial_savemap: Vec<u8>,
+    pub items: Vec<ItemData>,
+    pub weapons: Vec<WeaponData>,
+    pub armor: Vec<ArmorData>,
+    pub accessories: Vec<AccessoryData>,
+    pub materia: Vec<MateriaData>,
+    pub texts: KernelTexts,
+}
+
+/// PC-only text extension file holding the 18 kernel text sections without the 64 KiB section limit
+#[derive(Serialize, Debug, Clone)]
+pub struct Kernel2Bin {
+    pub texts: KernelTexts,
+    #[serde(skip)]
+    pub sections: Vec<Vec<u8>>,
+    #[serde(skip)]
+    pub original: Vec<u8>,
+}
@@ -1,3 +1,4 @@
+pub mod ai;
 pub mod general;
 pub mod battle;
 pub mod field;
@@ -7,6 +8,7 @@ pub mod world;
 pub mod party;
 pub mod chocobos;
 
+pub use ai::*;
 pub use general::*;
 pub use battle::*;
 pub use field::*;
@@ -1,3 +1,4 @@
+use super::kernel::MateriaData;
 use serde::Serialize;
 
 #[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
@@ -6,6 +7,45 @@ pub struct MateriaSlot {
   pub ap: u32, // Representing u24 as u32
 }
 
+impl MateriaSlot {
+  pub const MASTER_AP: u32 = 0xFFFFFF;
+
+  pub fn is_empty(&self) -> bool {
+    self.id == 0xFF
+  }
+
+  fn data<'a>(&self, materia_db: &'a [MateriaData]) -> Option<&'a MateriaData> {
+    if self.is_empty() {
+      return None;
+    }
+    materia_db.get(self.id as usize)
+  }
+
+  /// Current materia level, or 0 for an empty slot
+  pub fn level(&self, materia_db: &[MateriaData]) -> u8 {
+    match self.data(materia_db) {
+      Some(data) if self.ap == Self::MASTER_AP => data.max_level(),
+      Some(data) => data.level_for_ap(self.ap),
+      None => 0,
+    }
+  }
+
+  pub fn is_mastered(&self, materia_db: &[MateriaData]) -> bool {
+    match self.data(materia_db) {
+      Some(data) => self.level(materia_db) == data.max_level(),
+      None => false,
+    }
+  }
+
+  /// AP still needed to reach the next level, None when mastered or empty
+  pub fn ap_to_next_level(&self, materia_db: &[MateriaData]) -> Option<u32> {
+    let data = self.data(materia_db)?;
+    let level = self.level(materia_db);
+    let next = *data.level_ap().get(level as usize - 1)?;
+    Some(next.saturating_sub(self.ap))
+  }
+}
+
 #[derive(Serialize)]
 pub struct PartyMember {
   pub id: u8,
@@ -45,4 +85,26 @@ pub struct PartyMember {
   pub weapon_materia: Vec<MateriaSlot>,
   pub armor_materia: Vec<MateriaSlot>,
   pub exp_to_next_level: u32,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatRange {
+  pub min: u16,
+  pub max: u16,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LevelPrediction {
+  pub level: u8,
+  pub exp_required: u32, // Total EXP needed to reach this level
+  pub strength: StatRange,
+  pub vitality: StatRange,
+  pub magic: StatRange,
+  pub spirit: StatRange,
+  pub dexterity: StatRange,
+  pub luck: StatRange,
+  pub hp: StatRange,
+  pub mp: StatRange,
+  pub expected_hp_gain: f32,
+  pub expected_mp_gain: f32,
+}
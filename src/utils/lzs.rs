@@ -0,0 +1,69 @@
+// LZSS variant used by FF7 PC files: a 4-byte compressed length followed by groups of
+// one control byte (LSB first, 1 = literal, 0 = reference) and up to eight items.
+// References are two bytes addressing a 4 KiB ring buffer that starts at 0xFEE.
+
+const RING_SIZE: usize = 0x1000;
+const RING_START: usize = 0xFEE;
+const MIN_MATCH: usize = 3;
+
+pub fn lzs_decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < 4 {
+        return Err("LZS data is missing its length header".to_string());
+    }
+    let length = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let end = 4 + length;
+    if end > data.len() {
+        return Err(format!("LZS length {} exceeds data size {}", length, data.len() - 4));
+    }
+
+    let mut out: Vec<u8> = Vec::with_capacity(length * 2);
+    let mut pos = 4;
+    while pos < end {
+        let control = data[pos];
+        pos += 1;
+        for bit in 0..8 {
+            if pos >= end {
+                break;
+            }
+            if control & (1 << bit) != 0 {
+                out.push(data[pos]);
+                pos += 1;
+            } else {
+                if pos + 1 >= end {
+                    return Err("Truncated LZS reference".to_string());
+                }
+                let b0 = data[pos] as usize;
+                let b1 = data[pos + 1] as usize;
+                pos += 2;
+                let ring_offset = b0 | ((b1 & 0xF0) << 4);
+                let count = (b1 & 0x0F) + MIN_MATCH;
+
+                // Translate the ring buffer offset into an absolute output position.
+                // Positions before the start of the output refer to the zero-filled ring.
+                let ring_pos = (out.len() + RING_START) % RING_SIZE;
+                let back = (ring_pos + RING_SIZE - ring_offset) % RING_SIZE;
+                let back = if back == 0 { RING_SIZE } else { back };
+                for _ in 0..count {
+                    let byte = if back > out.len() { 0 } else { out[out.len() - back] };
+                    out.push(byte);
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Stores the data as literals only, which every LZS decoder accepts.
+pub fn lzs_compress(data: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(data.len() + data.len() / 8 + 1);
+    for chunk in data.chunks(8) {
+        body.push(0xFF);
+        body.extend_from_slice(chunk);
+    }
+
+    let mut out = Vec::with_capacity(body.len() + 4);
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend(body);
+    out
+}
@@ -1,3 +1,4 @@
+pub mod lzs;
 pub mod memory;
 pub mod process;
 
@@ -1,4 +1,5 @@
 use ff7_lib::ff7::data::battle::read_scene_bin_from_path;
+use ff7_lib::ff7::types::kernel::{ElementFlags, ElementalEffect, ElementalType, StatusFlags};
 use std::path::Path;
 
 /// Test the read_scene_bin_from_path function using a real scene.bin file
@@ -23,3 +24,641 @@ fn test_read_scene_bin() {
     // Output the number of scenes found for informational purposes
     println!("Successfully parsed {} scenes from scene.bin", num_scenes);
 }
+
+/// Attack records are decoded alongside the attack names
+#[test]
+fn test_scene_attack_data() {
+    let scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+
+    let bolt3 = scenes[0]
+        .attacks
+        .iter()
+        .find(|attack| attack.name == "Bolt3")
+        .expect("Bolt3 not found in the first scene");
+    assert_eq!(bolt3.id, 0x23);
+    assert_eq!(bolt3.data.mp_cost, 52);
+    assert_eq!(bolt3.data.power, 64);
+    assert_eq!(bolt3.data.attack_element, ElementFlags::BOLT);
+}
+
+/// Formation and enemy AI scripts are split per event slot
+#[test]
+fn test_scene_ai_scripts() {
+    use ff7_lib::ff7::types::battle::SceneAIEvent;
+
+    let scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    let scene = &scenes[0];
+
+    assert!(scene.formation_ai.iter().all(|ai| ai.is_none()));
+    assert!(scene.enemy_ai[1].is_none());
+
+    let ai = scene.enemy_ai[0].as_ref().expect("first enemy has no AI");
+    assert_eq!(ai.scripts.len(), 2);
+    let main = ai.script(SceneAIEvent::Main).expect("first enemy has no main script");
+    assert_eq!(main.offset, 0x20);
+    assert_eq!(main.bytecode.len(), 0x84 - 0x20);
+    assert_eq!(&main.bytecode[..3], &[0x02, 0x40, 0x21]);
+
+    let init = scene.enemy_ai[2].as_ref().unwrap().script(SceneAIEvent::Init).unwrap();
+    assert_eq!(init.bytecode.last(), Some(&0x73));
+
+    let with_formation_ai = scenes.iter().filter(|s| s.formation_ai[0].is_some()).count();
+    assert_eq!(with_formation_ai, 10);
+}
+
+/// AI bytecode disassembles into instructions and decompiles into readable pseudo-code
+#[test]
+fn test_ai_decompiler() {
+    use ff7_lib::ff7::ai::{decompile_script, disassemble_script};
+    use ff7_lib::ff7::types::ai::AIOperand;
+    use ff7_lib::ff7::types::battle::SceneAIEvent;
+
+    let scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    let scene = &scenes[0];
+
+    let init = scene.enemy_ai[2].as_ref().unwrap().script(SceneAIEvent::Init).unwrap();
+    let instructions = disassemble_script(init).unwrap();
+    let mnemonics: Vec<&str> = instructions.iter().map(|i| i.mnemonic).collect();
+    assert_eq!(mnemonics, vec!["PSHA", "PSHV", "PSH1", "ADD", "STORE", "END"]);
+    assert_eq!(instructions[0].operand, AIOperand::Address(0x2010));
+    assert_eq!(instructions[5].offset, 0x0A);
+    assert_eq!(
+        decompile_script(init, &scene.attacks).unwrap(),
+        "Global[2010] = (Global[2010] + 1);\nreturn;\n"
+    );
+
+    let main = scene.enemy_ai[0].as_ref().unwrap().script(SceneAIEvent::Main).unwrap();
+    let pseudo_code = decompile_script(main, &scene.attacks).unwrap();
+    assert!(pseudo_code.starts_with("if (Global[2140] == 0) {\n    if (Local[0000] == 0) {\n"));
+    assert!(pseudo_code.contains("    } else {\n        Local[0000] = 0;\n"));
+    assert!(!pseudo_code.contains("goto"));
+
+    let counter = scene.enemy_ai[2].as_ref().unwrap().script(SceneAIEvent::DeathCounter).unwrap();
+    let pseudo_code = decompile_script(counter, &scene.attacks).unwrap();
+    assert!(pseudo_code.contains("attack(0x20, 0x23); // Bolt3"));
+
+    // Mighty Grunt's counter passes its actor mask and itself to opcode 0x94
+    let grunt = scenes.iter().find(|scene| scene.id == 5).unwrap();
+    let counter = grunt.enemy_ai[1].as_ref().unwrap().script(SceneAIEvent::GeneralCounter).unwrap();
+    let pseudo_code = decompile_script(counter, &grunt.attacks).unwrap();
+    assert!(pseudo_code.contains("    scene(Local[0000], Global[2060]); // 0x94\n"));
+    assert!(pseudo_code.contains("attack(0x20, 0x176); // Grunt Death"));
+
+    // Every script in scene.bin keeps its stack balanced
+    for scene in &scenes {
+        for ai in scene.enemy_ai.iter().chain(scene.formation_ai.iter()).flatten() {
+            for script in &ai.scripts {
+                let pseudo_code = decompile_script(script, &scene.attacks).unwrap();
+                assert!(!pseudo_code.contains("<empty>"), "scene {} {:?}:\n{}", scene.id, script.event, pseudo_code);
+            }
+        }
+    }
+}
+
+/// Scenes written back to scene.bin parse to the same data, including edits
+#[test]
+fn test_write_scene_bin() {
+    use ff7_lib::ff7::data::battle::write_scene_bin;
+    use ff7_lib::ff7::types::battle::SceneAIData;
+
+    let mut scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    scenes[0].enemies[0].items[0].item_id = 0x0001;
+    scenes[0].enemies[0].hp = 1234;
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let lookup_table = write_scene_bin(&scenes, file.path()).expect("failed to write scene.bin");
+    let written = std::fs::read(file.path()).unwrap();
+    let reread = read_scene_bin_from_path(file.path()).unwrap();
+
+    assert_eq!(written.len() % 0x2000, 0);
+    assert_eq!(lookup_table.len(), 64);
+    assert_eq!(lookup_table[0], 0);
+    assert!(lookup_table.windows(2).all(|w| w[1] == 0xFF || w[1] > w[0]));
+
+    assert_eq!(reread.len(), scenes.len());
+    assert_eq!(reread[0].enemies[0].items[0].item_id, 0x0001);
+    assert_eq!(reread[0].enemies[0].hp, 1234);
+    for (original, written) in scenes.iter().zip(reread.iter()) {
+        assert_eq!(original.enemies[1].name, written.enemies[1].name);
+        assert_eq!(original.attacks.len(), written.attacks.len());
+        assert_eq!(original.formations[0].entries().count(), written.formations[0].entries().count());
+        for (a, b) in original.enemy_ai.iter().zip(written.enemy_ai.iter()) {
+            let bytecode = |ai: &Option<SceneAIData>| {
+                ai.as_ref().map(|ai| ai.scripts.iter().map(|s| s.bytecode.clone()).collect::<Vec<_>>())
+            };
+            assert_eq!(bytecode(a), bytecode(b));
+        }
+    }
+
+    // Missing ids are filled with blank scenes, so the scenes after a gap keep their id
+    let mut with_gap = scenes.clone();
+    with_gap.remove(1);
+    write_scene_bin(&with_gap, file.path()).unwrap();
+    let reread = read_scene_bin_from_path(file.path()).unwrap();
+    assert!(reread.iter().all(|scene| scene.id != 1 || scene.enemies.iter().all(|enemy| enemy.id == 0xFFFF)));
+    let second = reread.iter().find(|scene| scene.id == 2).unwrap();
+    assert_eq!(second.enemies[0].name, scenes[2].enemies[0].name);
+
+    // Scenes out of order or more scenes than the lookup table can index are rejected
+    let mut unsorted = scenes.clone();
+    unsorted.swap(1, 2);
+    assert!(write_scene_bin(&unsorted, file.path()).is_err());
+    let mut too_many = scenes.clone();
+    let mut extra = scenes[0].clone();
+    extra.id = too_many.len() as u16;
+    too_many.push(extra);
+    assert!(write_scene_bin(&too_many, file.path()).is_err());
+}
+
+/// An unmodified scene.bin is written back byte for byte, with the lookup table of the original file
+#[test]
+fn test_scene_bin_round_trips_byte_for_byte() {
+    use ff7_lib::ff7::data::battle::pack_scene_bin;
+
+    let original = std::fs::read("tests/data/scene.bin").unwrap();
+    let scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    let (packed, lookup_table) = pack_scene_bin(&scenes).unwrap();
+    assert!(packed == original);
+
+    // First scene index of every block, counted from the block headers of the original file
+    let mut expected = Vec::new();
+    let mut first_scene = 0;
+    for block in original.chunks(0x2000) {
+        expected.push(first_scene as u8);
+        first_scene += block[..64].chunks(4).filter(|offset| *offset != [0xFF; 4]).count();
+    }
+    expected.resize(64, 0xFF);
+    assert_eq!(lookup_table, expected);
+}
+
+/// Unmodified scenes are re-encoded to exactly the decompressed payloads they were read from
+#[test]
+fn test_scene_round_trip_is_lossless() {
+    use ff7_lib::ff7::data::battle::{encode_scene_data, unpack_scene_bin, write_scene_bin};
+    use ff7_lib::ff7::types::battle::SceneAIEvent;
+
+    let path = Path::new("tests/data/scene.bin");
+    let payloads = unpack_scene_bin(&std::fs::read(path).unwrap());
+    let mut scenes = read_scene_bin_from_path(path).unwrap();
+    assert_eq!(payloads.len(), scenes.len());
+    for (i, (scene, payload)) in scenes.iter().zip(payloads.iter()).enumerate() {
+        assert_eq!(scene.id, i as u16);
+        assert!(encode_scene_data(scene).unwrap() == *payload.as_ref().unwrap(), "scene {} differs", i);
+    }
+
+    // Edited AI scripts and removed formation enemies are laid out again
+    let scene = &mut scenes[0];
+    let last = scene.formations[0].enemies.iter().rposition(Option::is_some).unwrap();
+    scene.formations[0].enemies[last] = None;
+    let ai = scene.enemy_ai[2].as_mut().unwrap();
+    let init = ai.scripts.iter_mut().find(|s| s.event == SceneAIEvent::Init).unwrap();
+    let init_len = init.bytecode.len();
+    init.bytecode.insert(0, 0x74);
+    let encoded = encode_scene_data(scene).unwrap();
+    assert_eq!(encoded.len(), payloads[0].as_ref().unwrap().len());
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    write_scene_bin(&scenes, file.path()).unwrap();
+    let reread = read_scene_bin_from_path(file.path()).unwrap();
+    assert_eq!(reread[0].formations[0].entries().count(), scenes[0].formations[0].entries().count());
+    let init = reread[0].enemy_ai[2].as_ref().unwrap().script(SceneAIEvent::Init).unwrap();
+    assert_eq!(init.bytecode[0], 0x74);
+    assert_eq!(init.bytecode.len(), init_len + 1);
+    assert!(encode_scene_data(&reread[1]).unwrap() == payloads[1].as_deref().unwrap());
+}
+
+/// Formation entries are written back to their own slots, gaps included
+#[test]
+fn test_formation_slots_round_trip() {
+    use ff7_lib::ff7::data::battle::write_scene_bin;
+
+    let mut scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    let formation = &mut scenes[0].formations[0];
+    let entry = formation.entries().next().cloned().unwrap();
+    formation.enemies = std::array::from_fn(|_| None);
+    formation.enemies[0] = Some(entry.clone());
+    formation.enemies[2] = Some(entry);
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    write_scene_bin(&scenes, file.path()).unwrap();
+    let reread = read_scene_bin_from_path(file.path()).unwrap();
+    let occupied: Vec<bool> = reread[0].formations[0].enemies.iter().map(Option::is_some).collect();
+    assert_eq!(occupied, vec![true, false, true, false, false, false]);
+
+    let database = ff7_lib::ff7::types::battle::SceneDatabase::new(reread);
+    let slots: Vec<u8> = database.formation_details(0).unwrap().enemies.iter().map(|enemy| enemy.slot).collect();
+    assert_eq!(slots, vec![0, 2]);
+}
+
+/// Battle ids resolve to scene id / 4 and formation id % 4, with enemies joined to their definitions
+#[test]
+fn test_scene_database_formation() {
+    use ff7_lib::ff7::types::battle::SceneDatabase;
+
+    let database = SceneDatabase::new(read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap());
+
+    let battle_id = SceneDatabase::battle_id(1, 2);
+    assert_eq!(battle_id, 6);
+    let formation = database.formation(battle_id).unwrap();
+    assert_eq!(formation.entries().count(), database.scenes[1].formations[2].entries().count());
+
+    let details = database.formation_details(battle_id).unwrap();
+    assert_eq!(details.scene_id, 1);
+    assert_eq!(details.formation_index, 2);
+    assert!(!details.enemies.is_empty());
+    for enemy in &details.enemies {
+        let definition = enemy.enemy.as_ref().expect("formation enemy has no definition in its scene");
+        assert_eq!(definition.id, enemy.entry.enemy_id);
+        assert!(enemy.actor.is_none());
+    }
+
+    assert!(database.formation(database.scenes.len() as u16 * 4).is_none());
+
+    // A scene missing from the list doesn't shift the battle ids of later scenes
+    let mut scenes = database.scenes.clone();
+    let third = scenes.remove(2);
+    let database = SceneDatabase::new(scenes);
+    assert!(database.formation(SceneDatabase::battle_id(2, 0)).is_none());
+    let formation = database.formation(SceneDatabase::battle_id(3, 0)).unwrap();
+    assert_eq!(formation.setup.battle_location, database.scenes[2].formations[0].setup.battle_location);
+    assert_ne!(third.id, database.scenes[2].id);
+}
+
+#[test]
+fn test_battle_setup_flags() {
+    use ff7_lib::ff7::types::battle::{BattleLayout, BattleSetupFlags, CoverFlags, InitialConditionFlags};
+
+    let scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+
+    let setup = &scenes[0].formations[0].setup;
+    assert!(setup.flags.is_escapable());
+    assert!(!setup.flags.no_victory_pose());
+    assert!(!setup.flags.no_preemptive());
+    assert!(!setup.flags.show_timer());
+
+    let boss = &scenes[81].formations[0].setup;
+    assert!(!boss.flags.is_escapable());
+
+    // Battle 741, the second Schizo formation
+    let schizo = scenes.iter().find(|scene| scene.id == 185).unwrap();
+    assert!(schizo.formations[1].setup.flags.show_timer());
+
+    assert_eq!(BattleLayout::from(2), BattleLayout::BackAttack);
+    assert_eq!(BattleLayout::from(4).name(), "Pincer Attack");
+    assert_eq!(u8::from(BattleLayout::from(0xFF)), 0xFF);
+
+    let entry = scenes[0].formations[0].enemies[0].as_ref().unwrap();
+    assert!(entry.initial_condition_flags.contains(InitialConditionFlags::VISIBLE | InitialConditionFlags::TARGETABLE));
+
+    // Flags serialize as name lists, unknown bits by their value
+    let flags = BattleSetupFlags::SHOW_TIMER | BattleSetupFlags::ESCAPABLE | BattleSetupFlags::from_bits_retain(0x0100);
+    let json = serde_json::to_string(&flags).unwrap();
+    assert_eq!(json, r#"["Show Timer","Escapable","Unknown 0x0100"]"#);
+    assert_eq!(serde_json::from_str::<BattleSetupFlags>(&json).unwrap(), flags);
+    assert_eq!((CoverFlags::COLUMN_1 | CoverFlags::COLUMN_3).to_string(), "Column 1, Column 3");
+    let json = serde_json::to_string(&InitialConditionFlags::VISIBLE).unwrap();
+    assert_eq!(json, r#"["Visible"]"#);
+}
+
+#[test]
+fn test_named_flags() {
+    use ff7_lib::ff7::types::kernel::TargetFlags;
+
+    assert!(StatusFlags::empty().names().is_empty());
+    assert_eq!((StatusFlags::POISON | StatusFlags::BARRIER).names(), vec!["Poison", "Barrier"]);
+    assert_eq!(StatusFlags::IMPRISONED.to_string(), "Imprisoned");
+    assert_eq!((ElementFlags::FIRE | ElementFlags::HOLY).to_string(), "Fire, Holy");
+    assert_eq!(TargetFlags::empty().to_string(), "None");
+
+    let json = serde_json::to_string(&(StatusFlags::SLEEP | StatusFlags::DEATH_SENTENCE)).unwrap();
+    assert_eq!(json, r#"["Sleep","Death Sentence"]"#);
+    assert_eq!(serde_json::from_str::<StatusFlags>(&json).unwrap(), StatusFlags::SLEEP | StatusFlags::DEATH_SENTENCE);
+    assert!(serde_json::from_str::<ElementFlags>(r#"["Fire","Plasma"]"#).is_err());
+}
+
+#[test]
+fn test_element_defense() {
+    use ff7_lib::ff7::types::kernel::{ElementDefense, Elemental};
+
+    let rate = |element: u8, effect: u8| Elemental { element, effect };
+    let mut defense = ElementDefense::from_rates(&[rate(0, 2), rate(1, 4), rate(8, 5), rate(6, 6), rate(4, 0), rate(0xFF, 0xFF)]);
+    assert_eq!(defense.weak, ElementFlags::FIRE);
+    assert_eq!(defense.resisted, ElementFlags::ICE);
+    assert_eq!(defense.nullified, ElementFlags::HOLY);
+    assert_eq!(defense.absorbed, ElementFlags::WATER);
+
+    defense.add_equipment(0, ElementFlags::BOLT);
+    defense.add_equipment(2, ElementFlags::EARTH);
+    defense.add_equipment(0xFF, ElementFlags::WIND);
+    assert_eq!(defense.absorbed, ElementFlags::WATER | ElementFlags::BOLT);
+    assert_eq!(defense.resisted, ElementFlags::ICE | ElementFlags::EARTH);
+    assert_eq!(defense.nullified, ElementFlags::HOLY);
+}
+
+#[test]
+fn test_parse_battle_queue() {
+    use ff7_lib::ff7::data::battle::parse_battle_queue;
+
+    let mut bytes = vec![0xFFu8; 32];
+    bytes[..8].copy_from_slice(&[0x02, 0x00, 0x05, 0x20, 0x2A, 0x01, 0x01, 0x00]);
+    bytes[8..16].copy_from_slice(&[0x04, 0x01, 0x00, 0x02, 0x00, 0x00, 0xF0, 0x03]);
+
+    let queue = parse_battle_queue(&bytes);
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue[0].actor_index, 5);
+    assert_eq!(queue[0].command_id, 0x20);
+    assert_eq!(queue[0].attack_id, 0x12A);
+    assert_eq!(queue[0].targets(), vec![0]);
+    assert_eq!(queue[1].targets(), vec![4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_damage_formulas() {
+    use ff7_lib::ff7::damage::calculate_damage;
+    use ff7_lib::ff7::types::battle::{DamageActor, DamageAttack, DamageContext, SceneElementRate};
+
+    let actor = |level: u8, attack: u16, magic: u16, defense: u16| DamageActor {
+        level,
+        attack,
+        magic,
+        defense,
+        magic_defense: defense,
+        hp: 1000,
+        max_hp: 2000,
+        status: StatusFlags::empty(),
+        back_row: false,
+        back_damage_multiplier: 16,
+        element_rates: Vec::new(),
+    };
+    let attacker = actor(10, 50, 20, 0);
+    let mut target = actor(1, 0, 0, 100);
+
+    let hit = DamageAttack { damage_func: 0x11, power: 16, attack_element: ElementFlags::empty() };
+    let damage = calculate_damage(&attacker, &target, &hit, &DamageContext::default()).unwrap();
+    assert_eq!((damage.min, damage.max), (48, 52));
+    assert!(damage.expected > 48.0 && damage.expected < 52.0);
+
+    let critical = DamageContext { critical: true, ..Default::default() };
+    assert_eq!(calculate_damage(&attacker, &target, &hit, &critical).unwrap().max, 104);
+
+    target.magic_defense = 20;
+    let fire = DamageAttack { damage_func: 0x21, power: 8, attack_element: ElementFlags::FIRE };
+    assert_eq!(calculate_damage(&attacker, &target, &fire, &DamageContext::default()).unwrap().max, 86);
+
+    target.element_rates.push(SceneElementRate { element_type: 0, rate: 2 });
+    let weak = calculate_damage(&attacker, &target, &fire, &DamageContext::default()).unwrap();
+    assert_eq!((weak.min, weak.max), (160, 172));
+
+    target.element_rates[0].rate = 6;
+    assert_eq!(calculate_damage(&attacker, &target, &fire, &DamageContext::default()).unwrap().max, -80);
+    let spark = DamageAttack { damage_func: 0x24, power: 0, attack_element: ElementFlags::FIRE };
+    assert_eq!(calculate_damage(&attacker, &target, &spark, &DamageContext::default()).unwrap().max, -1);
+
+    let gravity = DamageAttack { damage_func: 0x22, power: 16, attack_element: ElementFlags::GRAVITY };
+    let halved = calculate_damage(&attacker, &target, &gravity, &DamageContext::default()).unwrap();
+    assert_eq!((halved.min, halved.max), (500, 500));
+
+    let unsupported = DamageAttack { damage_func: 0x1F, power: 1, attack_element: ElementFlags::empty() };
+    assert!(calculate_damage(&attacker, &target, &unsupported, &DamageContext::default()).is_err());
+}
+
+#[test]
+fn test_steal_drop_and_morph_chances() {
+    use ff7_lib::ff7::loot::{drop_chances, enemy_loot, steal_chances, steal_multiplier};
+    use ff7_lib::ff7::types::battle::{DamageActor, SceneEnemyItem};
+
+    let scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    let mut enemy = scenes[0].enemies[0].clone();
+    enemy.level = 10;
+    enemy.items = [
+        SceneEnemyItem { rate: 0x80 | 31, item_id: 0 },
+        SceneEnemyItem { rate: 0x80 | 63, item_id: 1 },
+        SceneEnemyItem { rate: 15, item_id: 2 },
+        SceneEnemyItem { rate: 0, item_id: 0xFFFF },
+    ];
+
+    assert_eq!(steal_multiplier(10, 10, false), 204.0 / 256.0);
+    assert_eq!(steal_multiplier(10, 10, true), 1.0);
+
+    let steals = steal_chances(10, &enemy, true, &[]);
+    assert_eq!(steals.len(), 2);
+    assert_eq!(steals[0].chance, 0.5);
+    assert_eq!(steals[1].chance, 0.5);
+
+    let drops = drop_chances(&enemy, &[String::from("Potion"), String::from("Hi-Potion"), String::from("X-Potion")]);
+    assert_eq!(drops.len(), 1);
+    assert_eq!(drops[0].slot, 2);
+    assert_eq!(drops[0].name.as_deref(), Some("X-Potion"));
+    assert_eq!(drops[0].chance, 0.25);
+
+    enemy.morph_item_id = 5;
+    enemy.hp = 9999;
+    let attacker = DamageActor::from(&enemy);
+    let loot = enemy_loot(&attacker, &enemy, enemy.hp, false, &[]).unwrap();
+    assert_eq!(loot.morph.unwrap().chance, 0.0);
+    let loot = enemy_loot(&attacker, &enemy, 1, false, &[]).unwrap();
+    assert_eq!(loot.morph.unwrap().chance, 1.0);
+}
+
+#[test]
+fn test_battle_recorder() {
+    use ff7_lib::ff7::timeline::{diff_snapshots, BattleRecorder};
+    use ff7_lib::ff7::types::battle::{BattleCharObj, BattleEvent, BattleQueuedAction, BattleSnapshot};
+
+    let actor = |index: u8, hp: u32, status: StatusFlags| BattleCharObj {
+        index,
+        name: format!("Actor {}", index),
+        flags: 0,
+        status,
+        initial_status: StatusFlags::empty(),
+        hp,
+        max_hp: 100,
+        mp: 10,
+        max_mp: 10,
+        atb: 0,
+        limit: 0,
+        scene_id: 0,
+        level: 1,
+        character_id: 0,
+        strength: 0,
+        weapon_attack: 0,
+        magic: 0,
+        dexterity: 0,
+        luck: 0,
+        defense: 0,
+        magic_defense: 0,
+        evade: 0,
+        magic_evade: 0,
+        weak_elements: ElementFlags::empty(),
+        resisted_elements: ElementFlags::empty(),
+        nullified_elements: ElementFlags::empty(),
+        absorbed_elements: ElementFlags::empty(),
+        back_damage_multiplier: 0,
+        row: 0,
+        last_covered: 0,
+        last_targets: 0,
+        last_attacker: 0,
+        gil: 0,
+        exp: 0,
+    };
+    let action = BattleQueuedAction {
+        priority: 2,
+        queue_position: 0,
+        actor_index: 0,
+        command_id: 1,
+        attack_id: 0,
+        target_mask: 0x0010,
+        command_name: Some(String::from("Attack")),
+        attack_name: None,
+    };
+
+    // Two identical queued actions: one leaving the queue still counts as started
+    let queued_twice = BattleSnapshot { in_game_time: 0, actors: Vec::new(), queue: vec![action.clone(), action.clone()] };
+    let queued_once = BattleSnapshot { in_game_time: 1, actors: Vec::new(), queue: vec![action.clone()] };
+    let started = diff_snapshots(&queued_twice, &queued_once);
+    assert_eq!(started.iter().filter(|event| matches!(event, BattleEvent::ActionStart { .. })).count(), 1);
+
+    let mut recorder = BattleRecorder::new(Vec::new(), 6, Vec::new());
+    let first = BattleSnapshot { in_game_time: 100, actors: vec![actor(0, 100, StatusFlags::empty()), actor(4, 30, StatusFlags::empty())], queue: vec![action] };
+    let second = BattleSnapshot { in_game_time: 101, actors: vec![actor(0, 100, StatusFlags::POISON), actor(4, 0, StatusFlags::empty())], queue: Vec::new() };
+
+    assert_eq!(recorder.record(first).unwrap()[0].event, BattleEvent::BattleStart { battle_id: 6 });
+    let events: Vec<BattleEvent> = recorder.record(second).unwrap().into_iter().map(|entry| entry.event).collect();
+    assert!(matches!(&events[0], BattleEvent::ActionStart { actor_index: 0, targets, .. } if targets == &vec![4]));
+    assert!(events.contains(&BattleEvent::StatusChange { actor_index: 0, name: String::from("Actor 0"), added: vec!["Poison"], removed: vec![] }));
+    assert!(events.contains(&BattleEvent::HpChange { actor_index: 4, name: String::from("Actor 4"), from: 30, to: 0, delta: -30 }));
+    assert!(events.contains(&BattleEvent::KnockedOut { actor_index: 4, name: String::from("Actor 4") }));
+
+    let log = String::from_utf8(recorder.finish(102).unwrap()).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[0], r#"{"in_game_time":100,"event":"battle_start","battle_id":6}"#);
+    assert!(lines[5].contains(r#""event":"battle_end""#));
+
+    // Live actors feed the damage formulas with their weapon attack and every element mask
+    use ff7_lib::ff7::types::battle::DamageActor;
+    let mut ally = actor(0, 100, StatusFlags::empty());
+    ally.strength = 30;
+    ally.weapon_attack = 18;
+    ally.weak_elements = ElementFlags::ICE;
+    ally.absorbed_elements = ElementFlags::FIRE;
+    let damage_actor = DamageActor::from(&ally);
+    assert_eq!(damage_actor.attack, 48);
+    let rates: Vec<(u8, u8)> = damage_actor.element_rates.iter().map(|rate| (rate.element_type, rate.rate)).collect();
+    assert_eq!(rates, vec![(1, ElementalEffect::DoubleDamage as u8), (0, ElementalEffect::Absorb as u8)]);
+}
+
+#[test]
+fn test_bestiary_export() {
+    use ff7_lib::ff7::bestiary::{bestiary_to_csv, bestiary_to_json, build_bestiary};
+    use ff7_lib::ff7::types::battle::SceneDatabase;
+
+    let scenes = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    let bestiary = build_bestiary(&scenes, &[]);
+
+    let mut ids: Vec<u16> = bestiary.iter().map(|entry| entry.id).collect();
+    ids.dedup();
+    assert_eq!(ids.len(), bestiary.len());
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Battle ids come from the scene ids, not from positions in the list
+    let without_first = build_bestiary(&scenes[1..], &[]);
+    let formations = without_first.iter().flat_map(|entry| entry.formations.iter());
+    assert!(formations.clone().all(|formation| formation.scene_id >= 1 && formation.battle_id / 4 == formation.scene_id));
+    assert!(formations.count() > 0);
+
+    let database = SceneDatabase::new(scenes);
+    for entry in &bestiary {
+        for formation in &entry.formations {
+            let formation = database.formation(formation.battle_id).unwrap();
+            assert!(formation.entries().any(|enemy| enemy.enemy_id == entry.id));
+        }
+    }
+
+    let csv = bestiary_to_csv(&bestiary);
+    assert_eq!(csv.lines().count(), bestiary.len() + 1);
+    assert!(csv.starts_with("id,name,level,hp"));
+    assert!(bestiary_to_json(&bestiary).unwrap().contains("\"formations\""));
+}
+
+#[test]
+fn test_scene_queries() {
+    use ff7_lib::ff7::query::{enemies_at_location, enemies_weak_to, enemies_with_attack, enemies_with_item, formations_at_location};
+    use ff7_lib::ff7::types::battle::{ItemSource, SceneDatabase};
+
+    let database = SceneDatabase::new(read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap());
+    let scene = &database.scenes[1];
+    let enemy = &scene.enemies[0];
+
+    let item = enemy.items.iter().find(|item| item.item_id != 0xFFFF).unwrap();
+    let source = if item.is_steal() { ItemSource::Steal } else { ItemSource::Drop };
+    let matches = enemies_with_item(&database, item.item_id, source);
+    let found = matches.iter().find(|found| found.enemy_id == enemy.id).unwrap();
+    for battle_id in &found.battle_ids {
+        assert!(database.formation(*battle_id).unwrap().entries().any(|entry| entry.enemy_id == enemy.id));
+    }
+
+    let attack_id = enemy.enemy_attack_ids[0];
+    assert!(enemies_with_attack(&database, attack_id).iter().any(|found| found.enemy_id == enemy.id));
+
+    for found in enemies_weak_to(&database, ElementalType::Fire) {
+        let definition = database.scenes.iter().flat_map(|scene| scene.enemies.iter()).find(|e| e.id == found.enemy_id).unwrap();
+        assert!(definition.element_rates.iter().any(|rate| rate.element_type == ElementalType::Fire as u8 && rate.rate == ElementalEffect::DoubleDamage as u8));
+    }
+
+    let location = scene.formations[0].setup.battle_location;
+    let battle_ids = formations_at_location(&database, location);
+    assert!(battle_ids.contains(&SceneDatabase::battle_id(1, 0)));
+    let at_location = enemies_at_location(&database, location);
+    assert!(at_location.iter().all(|found| found.battle_ids.iter().all(|id| battle_ids.contains(id))));
+
+    // Battle ids stay tied to their scene when an earlier scene is missing
+    let without_first = SceneDatabase::new(database.scenes[1..].to_vec());
+    assert!(formations_at_location(&without_first, location).contains(&SceneDatabase::battle_id(1, 0)));
+}
+
+#[test]
+fn test_diff_scenes() {
+    use ff7_lib::ff7::diff::{diff_scenes, render_scene_diff};
+    use ff7_lib::ff7::types::battle::SceneChangeKind;
+
+    let vanilla = read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap();
+    assert!(diff_scenes(&vanilla, &vanilla).unwrap().is_empty());
+
+    let mut modded = vanilla.clone();
+    let old_hp = modded[3].enemies[0].hp;
+    modded[3].enemies[0].hp = old_hp + 100;
+    modded[3].enemies[0].items[0].rate ^= 0x01;
+    modded[3].formations[0].enemies[0].as_mut().unwrap().pos_x += 10;
+    let mut attack = modded[3].attacks[0].clone();
+    attack.id = 0x1FF;
+    modded[3].attacks.push(attack);
+
+    let changes = diff_scenes(&vanilla, &modded).unwrap();
+    assert!(changes.iter().all(|change| change.scene_id == 3));
+    let paths: Vec<&str> = changes.iter().map(|change| change.path.as_str()).collect();
+    assert!(paths.contains(&"enemies[0].hp"));
+    assert!(paths.contains(&"enemies[0].items[0].rate"));
+    assert!(paths.contains(&"formations[0].enemies[0].pos_x"));
+    let hp = changes.iter().find(|change| change.path == "enemies[0].hp").unwrap();
+    assert_eq!(hp.change, SceneChangeKind::Changed { old: old_hp.to_string(), new: (old_hp + 100).to_string() });
+    let added = changes.iter().find(|change| change.path == "attacks[0x01FF]").unwrap();
+    assert!(matches!(added.change, SceneChangeKind::Added { .. }));
+
+    let text = render_scene_diff(&changes);
+    assert!(text.starts_with("Scene 3:\n"));
+    assert!(text.contains(&format!("  ~ enemies[0].hp: {} -> {}\n", old_hp, old_hp + 100)));
+    assert!(text.contains("  + attacks[0x01FF]: "));
+
+    let changes = diff_scenes(&vanilla, &vanilla[..vanilla.len() - 1]).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(changes[0].change, SceneChangeKind::Removed { .. }));
+
+    // A missing scene in the middle doesn't pair the later scenes with the wrong ones
+    let mut with_gap = vanilla.clone();
+    with_gap.remove(1);
+    let changes = diff_scenes(&vanilla, &with_gap).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].scene_id, 1);
+    assert!(matches!(changes[0].change, SceneChangeKind::Removed { .. }));
+}
@@ -0,0 +1,99 @@
+use ff7_lib::ff7::data::battle::read_scene_bin_from_path;
+use ff7_lib::ff7::data::field::parse_field_file_encounters;
+use ff7_lib::ff7::encounters::encounter_report;
+use ff7_lib::ff7::types::battle::SceneDatabase;
+use ff7_lib::ff7::types::field::EncounterKind;
+use ff7_lib::utils::lzs::lzs_compress;
+use std::path::Path;
+
+fn pack_pair(encounter_id: u16, rate: u8) -> [u8; 2] {
+    ((rate as u16) << 10 | encounter_id).to_le_bytes()
+}
+
+/// A decompressed field file with empty sections except for the encounter tables in section 7
+fn build_field_file() -> Vec<u8> {
+    let mut table = vec![1u8, 32];
+    for (id, rate) in [(4, 48), (5, 16), (0, 0), (0, 0), (0, 0), (0, 0), (6, 8), (0, 0), (0, 0), (0, 0)] {
+        table.extend_from_slice(&pack_pair(id, rate));
+    }
+    table.extend_from_slice(&[0, 0]);
+    let mut second = table.clone();
+    second[0] = 0;
+
+    let mut data = vec![0u8, 0];
+    data.extend_from_slice(&9u32.to_le_bytes());
+    let sections_start = 6 + 9 * 4;
+    let mut sections = Vec::new();
+    let mut offsets = Vec::new();
+    for section in 0..9 {
+        offsets.push((sections_start + sections.len()) as u32);
+        let body = if section == 6 { [table.clone(), second.clone()].concat() } else { Vec::new() };
+        sections.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        sections.extend_from_slice(&body);
+    }
+    for offset in offsets {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(&sections);
+    data
+}
+
+#[test]
+fn test_field_file_encounters() {
+    let field = build_field_file();
+    let tables = parse_field_file_encounters(&field).unwrap();
+    assert!(tables.table1.active);
+    assert!(!tables.table2.active);
+    assert_eq!(tables.table1.encounter_rate, 32);
+    assert_eq!(tables.table1.normal_encounters[0].encounter_id, 4);
+    assert_eq!(tables.table1.normal_encounters[0].rate, 48);
+    assert_eq!(tables.table1.back_attacks[0].encounter_id, 6);
+
+    let compressed = parse_field_file_encounters(&lzs_compress(&field)).unwrap();
+    assert_eq!(compressed.table1.normal_encounters[1].encounter_id, 5);
+
+    assert!(parse_field_file_encounters(&field[..20]).is_err());
+}
+
+#[test]
+fn test_encounter_report() {
+    let database = SceneDatabase::new(read_scene_bin_from_path(Path::new("tests/data/scene.bin")).unwrap());
+    let tables = parse_field_file_encounters(&build_field_file()).unwrap();
+    let report = encounter_report(&tables, &database, 10, &[]);
+
+    let slots = &report.table1.slots;
+    assert_eq!(slots.len(), 3);
+    assert_eq!(slots[0].kind, EncounterKind::BackAttack);
+    assert_eq!(slots[0].percentage, 12.5);
+    assert_eq!(slots[1].battle_id, 4);
+    assert_eq!(slots[1].percentage, 87.5 * 0.75);
+
+    let details = database.formation_details(4).unwrap();
+    let names: Vec<&str> = slots[1].enemies.iter().map(|enemy| enemy.name.as_str()).collect();
+    let expected: Vec<&str> = details.enemies.iter().filter_map(|enemy| enemy.enemy.as_ref()).map(|enemy| enemy.name.as_str()).collect();
+    assert_eq!(names, expected);
+    assert!(slots[1].enemies.iter().all(|enemy| enemy.hp > 0));
+}
+
+#[test]
+fn test_encode_encounter_set() {
+    use ff7_lib::ff7::data::field::{encode_encounter_set, parse_encounter_set};
+
+    let field = build_field_file();
+    let tables = parse_field_file_encounters(&field).unwrap();
+    let mut set = tables.table1.clone();
+
+    let encoded = encode_encounter_set(&set).unwrap();
+    assert_eq!(encoded.len(), 22);
+    let start = field.len() - 8 - 48; // Sections 8 and 9 are empty length words
+    assert_eq!(encoded[..], field[start..start + 22]);
+    assert_eq!(parse_encounter_set(&[encoded.clone(), vec![0, 0]].concat()).unwrap().normal_encounters[1].rate, 16);
+
+    set.normal_encounters[1].rate = 15;
+    assert!(encode_encounter_set(&set).is_err());
+    set.active = false;
+    assert!(encode_encounter_set(&set).is_ok());
+
+    set.normal_encounters[0].encounter_id = 0x400;
+    assert!(encode_encounter_set(&set).is_err());
+}
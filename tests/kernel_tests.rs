@@ -0,0 +1,300 @@
+use ff7_lib::ff7::data::kernel_bin::{kernel2_from_kernel, pack_kernel2_bin, pack_kernel_bin, parse_kernel2_bin, parse_kernel_bin};
+use ff7_lib::ff7::types::kernel::{MateriaSlotKind, MateriaSlotLayout, MateriaType};
+use ff7_lib::ff7::growth::{exp_table, predict_level_ups, predicted_exp_to_next_level};
+use ff7_lib::ff7::types::party::{MateriaSlot, PartyMember, StatRange};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Builds a text section with the given strings (plain ASCII, shifted to FF7 text encoding)
+fn text_section(strings: &[&str]) -> Vec<u8> {
+    let mut offsets = Vec::new();
+    let mut body = Vec::new();
+    let table_size = strings.len() * 2;
+    for s in strings {
+        offsets.extend_from_slice(&((table_size + body.len()) as u16).to_le_bytes());
+        body.extend(s.bytes().map(|b| b - 0x20));
+        body.push(0xFF);
+    }
+    offsets.extend(body);
+    offsets
+}
+
+/// Builds a synthetic kernel.bin with recognizable contents in every section
+fn build_kernel_bin() -> Vec<u8> {
+    let mut sections: Vec<Vec<u8>> = vec![
+        vec![0xFF; 32 * 8],
+        vec![0x00; 128 * 28],
+        vec![0x00; 0x1004],
+        vec![0x00; 4340],
+        vec![0xFF; 128 * 28],
+        vec![0x00; 128 * 44],
+        vec![0x00; 32 * 36],
+        vec![0x00; 32 * 16],
+        vec![0x00; 96 * 20],
+    ];
+    // Potion: restores HP with power 10
+    sections[4][0x0e] = 0x0A;
+    sections[4][0x0f] = 10;
+    // Buster Sword: attack 18, two linked growth slots, equippable by Cloud only
+    sections[5][0x04] = 18;
+    sections[5][0x0E] = 0x01;
+    sections[5][0x1C] = 0x06;
+    sections[5][0x1D] = 0x07;
+    sections[5][0x14..0x18].copy_from_slice(&[0xFF; 4]);
+    // Materia 0: levels at 2000 and 6000 AP, magic type
+    sections[8][0..8].copy_from_slice(&[20, 0, 60, 0, 0xFF, 0xFF, 0xFF, 0xFF]);
+    sections[8][0x0D] = 0x09;
+    // First growth curve: gradient 5, base 20 for the first bracket
+    sections[2][0x28C] = 5;
+    sections[2][0x28D] = 20;
+    sections[2][0x268..0x274].copy_from_slice(&[0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 3, 3]);
+    sections[2][0xE8C..0xE90].copy_from_slice(&[0xB1, 0xCA, 0x1C, 0x32]);
+    sections[2][0x274..0x280].copy_from_slice(&[40, 50, 50, 60, 70, 80, 90, 100, 110, 120, 130, 150]);
+    for i in 9..27 {
+        sections.push(text_section(&[&format!("Text{}", i), "Other"]));
+    }
+
+    let mut file = Vec::new();
+    for (i, data) in sections.iter().enumerate() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        file.extend_from_slice(&(compressed.len() as u16).to_le_bytes());
+        file.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        file.extend_from_slice(&(i as u16).to_le_bytes());
+        file.extend(compressed);
+    }
+    file
+}
+
+#[test]
+fn test_parse_kernel_bin() {
+    let kernel = parse_kernel_bin(&build_kernel_bin()).expect("failed to parse kernel.bin");
+
+    assert_eq!(kernel.sections.len(), 27);
+    assert_eq!(kernel.commands.len(), 32);
+    assert_eq!(kernel.attacks.len(), 128);
+    assert_eq!(kernel.items.len(), 128);
+    assert_eq!(kernel.items[0].damage_func, 0x0A);
+    assert_eq!(kernel.items[0].power, 10);
+    assert_eq!(kernel.weapons.len(), 128);
+    assert_eq!(kernel.armor.len(), 32);
+    assert_eq!(kernel.accessories.len(), 32);
+    let sword = &kernel.weapons[0];
+    assert_eq!(sword.attack, 18);
+    assert!(sword.can_equip(0));
+    assert!(!sword.can_equip(1));
+    assert!(sword.stat_boosts.is_empty());
+    assert_eq!(sword.materia_slots[0], MateriaSlotLayout { kind: MateriaSlotKind::LinkedLeft, growth: true });
+    assert_eq!(sword.materia_slots[1], MateriaSlotLayout { kind: MateriaSlotKind::LinkedRight, growth: true });
+    assert_eq!(sword.materia_slots[2].kind, MateriaSlotKind::None);
+    assert_eq!(kernel.battle_growth.characters.len(), 11);
+    assert_eq!(kernel.battle_growth.stat_curves.len(), 64);
+    assert_eq!(kernel.battle_growth.stat_curves[0].gradients[0], 5);
+    assert_eq!(kernel.battle_growth.stat_curves[0].bases[0], 20);
+    assert_eq!(kernel.battle_growth.random_table.len(), 256);
+    let table = ff7_lib::ff7::rng::random_table_from_kernel(&kernel.battle_growth, 2).unwrap();
+    assert_eq!(table.values[..4], [0xB1, 0xCA, 0x1C, 0x32]);
+    assert_eq!(table.index, 2);
+    assert_eq!(kernel.texts.item_names, vec!["Text19", "Other"]);
+    assert_eq!(kernel.texts.summon_attack_names[0], "Text26");
+}
+
+#[test]
+fn test_kernel_bin_round_trip() {
+    let original = build_kernel_bin();
+    let mut kernel = parse_kernel_bin(&original).expect("failed to parse kernel.bin");
+
+    // Unmodified kernel must be written back byte for byte
+    let packed = pack_kernel_bin(&kernel).expect("failed to pack kernel.bin");
+    assert_eq!(packed, original);
+
+    // Modified sections are re-encoded and parse back to the new values
+    kernel.items[0].power = 50;
+    kernel.attacks[3].mp_cost = 12;
+    kernel.weapons[0].hit_rate = 96;
+    kernel.texts.item_names[1] = String::from("Hi-Potion");
+    let packed = pack_kernel_bin(&kernel).expect("failed to pack modified kernel.bin");
+    let reparsed = parse_kernel_bin(&packed).expect("failed to parse modified kernel.bin");
+    assert_eq!(reparsed.items[0].power, 50);
+    assert_eq!(reparsed.items[0].damage_func, 0x0A);
+    assert_eq!(reparsed.attacks[3].mp_cost, 12);
+    assert_eq!(reparsed.weapons[0].hit_rate, 96);
+    assert_eq!(reparsed.weapons[0].attack, 18);
+    assert_eq!(reparsed.texts.item_names, vec!["Text19", "Hi-Potion"]);
+    assert_eq!(reparsed.texts.weapon_names, kernel.texts.weapon_names);
+}
+
+/// Editing one string leaves the bytes of the other strings in its section alone
+#[test]
+fn test_kernel_text_edit_keeps_other_strings() {
+    use ff7_lib::ff7::data::kernel_bin::parse_kernel_text_section;
+
+    let mut kernel = parse_kernel_bin(&build_kernel_bin()).expect("failed to parse kernel.bin");
+    // 0xE2 decodes to ", " which encodes back as two characters; 0xE5 doesn't decode at all
+    let battle_text = [0x04, 0x00, 0x0A, 0x00, 0x21, 0xE2, 0xE5, 0x22, 0xFF, 0xFF, 0x21, 0xFF];
+    kernel.sections[25].data = battle_text.to_vec();
+    kernel.texts.battle_texts = parse_kernel_text_section(&battle_text).unwrap();
+    assert_eq!(kernel.texts.battle_texts, vec!["???", "A"]);
+
+    kernel.texts.battle_texts[1] = String::from("B");
+    let reparsed = parse_kernel_bin(&pack_kernel_bin(&kernel).unwrap()).expect("failed to parse packed kernel.bin");
+    let section = &reparsed.sections[25].data;
+    assert_eq!(&section[4..9], &battle_text[4..9]);
+    assert_eq!(reparsed.texts.battle_texts, vec!["???", "B"]);
+}
+
+#[test]
+fn test_kernel_bin_regzip() {
+    let original = build_kernel_bin();
+    let mut kernel = parse_kernel_bin(&original).expect("failed to parse kernel.bin");
+    let original_streams: Vec<Vec<u8>> = kernel.sections.iter().map(|section| section.compressed.clone()).collect();
+
+    // Without the original streams every section goes through the gzip encoder
+    for section in kernel.sections.iter_mut() {
+        section.compressed.clear();
+    }
+    kernel.items[0].power = 50;
+    let packed = pack_kernel_bin(&kernel).expect("failed to pack kernel.bin");
+
+    let mut pos = 0;
+    for (i, section) in kernel.sections.iter().enumerate() {
+        let compressed_size = u16::from_le_bytes([packed[pos], packed[pos + 1]]) as usize;
+        let decompressed_size = u16::from_le_bytes([packed[pos + 2], packed[pos + 3]]) as usize;
+        let file_type = u16::from_le_bytes([packed[pos + 4], packed[pos + 5]]);
+        pos += 6;
+        let stream = &packed[pos..pos + compressed_size];
+        pos += compressed_size;
+
+        let mut decompressed = Vec::new();
+        GzDecoder::new(stream).read_to_end(&mut decompressed).expect("failed to decompress section");
+        assert_eq!(file_type, section.file_type);
+        assert_eq!(decompressed_size, decompressed.len());
+        if i == 4 {
+            assert_eq!(decompressed[0x0F], 50);
+        } else {
+            assert_eq!(decompressed, section.data);
+        }
+        assert_eq!(&stream[..4], &original_streams[i][..4]);
+    }
+    assert_eq!(pos, packed.len());
+
+    // A modified section keeps the gzip header fields of the original stream
+    let mut kernel = parse_kernel_bin(&original).expect("failed to parse kernel.bin");
+    kernel.items[0].power = 50;
+    let reparsed = parse_kernel_bin(&pack_kernel_bin(&kernel).unwrap()).expect("failed to parse packed kernel.bin");
+    assert_eq!(reparsed.sections[4].compressed[4..10], original_streams[4][4..10]);
+    assert_eq!(reparsed.items[0].power, 50);
+}
+
+#[test]
+fn test_kernel2_bin_round_trip() {
+    let kernel = parse_kernel_bin(&build_kernel_bin()).expect("failed to parse kernel.bin");
+    let mut kernel2 = kernel2_from_kernel(&kernel).expect("failed to build kernel2.bin");
+
+    let packed = pack_kernel2_bin(&kernel2).expect("failed to pack kernel2.bin");
+    let reparsed = parse_kernel2_bin(&packed).expect("failed to parse kernel2.bin");
+    assert_eq!(reparsed.texts.item_names, kernel.texts.item_names);
+    assert_eq!(pack_kernel2_bin(&reparsed).unwrap(), packed);
+
+    kernel2.texts.magic_names[0] = String::from("Fire");
+    let packed = pack_kernel2_bin(&kernel2).expect("failed to pack modified kernel2.bin");
+    let reparsed = parse_kernel2_bin(&packed).expect("failed to parse modified kernel2.bin");
+    assert_eq!(reparsed.texts.magic_names, vec!["Fire", "Other"]);
+
+    let mut truncated = kernel;
+    truncated.sections.truncate(9);
+    assert!(kernel2_from_kernel(&truncated).is_err());
+}
+
+#[test]
+fn test_materia_levels() {
+    let kernel = parse_kernel_bin(&build_kernel_bin()).expect("failed to parse kernel.bin");
+    let materia = &kernel.materia;
+    assert_eq!(materia.len(), 96);
+    assert_eq!(materia[0].materia_type, MateriaType::Magic);
+    assert_eq!(materia[0].max_level(), 3);
+
+    let fresh = MateriaSlot { id: 0, ap: 0 };
+    assert_eq!(fresh.level(materia), 1);
+    assert_eq!(fresh.ap_to_next_level(materia), Some(2000));
+    assert!(!fresh.is_mastered(materia));
+
+    let leveled = MateriaSlot { id: 0, ap: 2500 };
+    assert_eq!(leveled.level(materia), 2);
+    assert_eq!(leveled.ap_to_next_level(materia), Some(3500));
+
+    let mastered = MateriaSlot { id: 0, ap: 6000 };
+    assert_eq!(mastered.level(materia), 3);
+    assert!(mastered.is_mastered(materia));
+    assert_eq!(mastered.ap_to_next_level(materia), None);
+
+    let empty = MateriaSlot { id: 0xFF, ap: 0 };
+    assert_eq!(empty.level(materia), 0);
+    assert_eq!(empty.ap_to_next_level(materia), None);
+}
+
+fn level_one_member() -> PartyMember {
+    PartyMember {
+        id: 0,
+        name: String::from("Cloud"),
+        level: 1,
+        strength: 20,
+        vitality: 20,
+        magic: 20,
+        spirit: 20,
+        dexterity: 20,
+        luck: 20,
+        strength_bonus: 0,
+        vitality_bonus: 0,
+        magic_bonus: 0,
+        spirit_bonus: 0,
+        dexterity_bonus: 0,
+        luck_bonus: 0,
+        limit_level: 1,
+        status: 0,
+        order: 0,
+        hp: 800,
+        base_hp: 800,
+        max_hp: 800,
+        mp: 40,
+        base_mp: 40,
+        max_mp: 40,
+        limit: 0,
+        exp: 1,
+        weapon: 0,
+        armor: 0,
+        accessory: 0xFF,
+        limit_skills: 0,
+        kills: 0,
+        limit_1_1_uses: 0,
+        limit_2_1_uses: 0,
+        limit_3_1_uses: 0,
+        weapon_materia: Vec::new(),
+        armor_materia: Vec::new(),
+        exp_to_next_level: 1,
+    }
+}
+
+#[test]
+fn test_level_up_prediction() {
+    let kernel = parse_kernel_bin(&build_kernel_bin()).expect("failed to parse kernel.bin");
+    let growth = &kernel.battle_growth;
+
+    // Curve 0 has gradient 5 in the first bracket: level N costs 5 * N^2 / 10 EXP
+    let table = exp_table(growth, 0).unwrap();
+    assert_eq!(table[2], 2);
+    assert_eq!(table[3], 6);
+
+    let member = level_one_member();
+    assert_eq!(predicted_exp_to_next_level(&member, growth).unwrap(), 1);
+
+    let predictions = predict_level_ups(&member, growth, 5).unwrap();
+    assert_eq!(predictions.len(), 4);
+    assert_eq!(predictions[0].level, 2);
+    assert_eq!(predictions[0].exp_required, 2);
+    assert_eq!(predictions[0].strength, StatRange { min: 20, max: 22 });
+    assert!(predictions[3].strength.max >= predictions[0].strength.max);
+}
@@ -0,0 +1,69 @@
+use ff7_lib::ff7::rng::{advance_step, encounter_random, encounter_triggers, next_random, random_table};
+use ff7_lib::ff7::types::general::EncounterRng;
+
+#[test]
+fn test_random_table_and_encounter_rng() {
+    let values: Vec<u8> = (0..=255).map(|i: u8| i.wrapping_mul(7)).collect();
+    assert!(random_table(&values[..10], 0).is_err());
+
+    let mut table = random_table(&values, 254).unwrap();
+    assert_eq!(next_random(&mut table), values[254]);
+    assert_eq!(next_random(&mut table), values[255]);
+    assert_eq!(next_random(&mut table), values[0]);
+
+    let mut rng = EncounterRng { step_id: 255, step_offset: 250, step_fraction: 0, danger_value: 0 };
+    assert_eq!(encounter_random(&rng, &table), values[249]);
+    advance_step(&mut rng, 64);
+    assert_eq!((rng.step_id, rng.step_offset, rng.danger_value), (0, 7, 64));
+    assert_eq!(encounter_random(&rng, &table), values[7]);
+
+    rng.danger_value = (values[7] as u32) << 8;
+    assert!(!encounter_triggers(&rng, &table));
+    rng.danger_value += 1;
+    assert!(encounter_triggers(&rng, &table));
+}
+
+#[test]
+fn test_encounter_prediction() {
+    use ff7_lib::ff7::encounters::{danger_increment, formation_chances, predict_encounter, steps_until_encounter};
+    use ff7_lib::ff7::types::field::{EncounterKind, EncounterPair, FieldEncounterSet};
+
+    let pair = |encounter_id: u16, rate: u8| EncounterPair { encounter_id, rate };
+    let set = FieldEncounterSet {
+        active: true,
+        encounter_rate: 32,
+        normal_encounters: vec![pair(100, 32), pair(101, 16), pair(102, 16), pair(0, 0), pair(0, 0), pair(0, 0)],
+        back_attacks: vec![pair(103, 8), pair(0, 0)],
+        side_attack: pair(0, 0),
+        pincer_attack: pair(104, 8),
+    };
+
+    assert_eq!(danger_increment(32, false), 128);
+    assert_eq!(danger_increment(32, true), 256);
+
+    let chances = formation_chances(&set);
+    assert_eq!(chances[0].kind, EncounterKind::BackAttack);
+    assert_eq!(chances[0].chance, 0.125);
+    assert_eq!(chances[1].kind, EncounterKind::PincerAttack);
+    assert_eq!(chances[1].chance, 0.875 * 0.125);
+    let total: f32 = chances.iter().map(|chance| chance.chance).sum();
+    assert!((total - 1.0).abs() < 1e-6);
+
+    // Every roll is 4, so an encounter needs a danger value above 0x400
+    let table = random_table(&[4; 256], 0).unwrap();
+    let rng = EncounterRng { step_id: 0, step_offset: 0, step_fraction: 0, danger_value: 0 };
+    assert_eq!(steps_until_encounter(&rng, &table, 32, false), Some(9));
+    assert_eq!(steps_until_encounter(&rng, &table, 32, true), Some(5));
+
+    // Being partway into a step doesn't change how many steps are left
+    let moving = EncounterRng { step_fraction: 0x40, ..rng };
+    assert_eq!(steps_until_encounter(&moving, &table, 32, false), Some(9));
+    assert_eq!(predict_encounter(&moving, &table, &set).running_steps, Some(5));
+
+    let prediction = predict_encounter(&rng, &table, &set);
+    assert_eq!(prediction.walking_steps, Some(9));
+    assert_eq!(prediction.formations.len(), 5);
+
+    let inactive = FieldEncounterSet { active: false, ..set };
+    assert_eq!(predict_encounter(&rng, &table, &inactive).walking_steps, None);
+}